@@ -0,0 +1,35 @@
+//! Solve a localized variant of part 2, e.g.
+//!
+//!     cargo run -p day-01 --bin localized -- de dojo-input.txt
+//!
+//! where the first argument is a shipped locale pack (en, de, fr, roman) or the path to a
+//! vocabulary file in the format of `day-01/locales/*.txt`. ASCII digits always count.
+use day_01::{part2::process_with, vocabulary::Vocabulary};
+use miette::{Context, IntoDiagnostic};
+
+fn main() -> miette::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let mut args = std::env::args().skip(1);
+    let locale = args.next().unwrap_or_else(|| "en".to_string());
+
+    let vocabulary = if Vocabulary::locales().any(|name| name == locale) {
+        Vocabulary::locale(&locale)?
+    } else {
+        let text = std::fs::read_to_string(&locale)
+            .into_diagnostic()
+            .with_context(|| format!("read vocabulary {locale}"))?;
+        Vocabulary::parse(&text)?
+    };
+    let vocabulary = Vocabulary::digits().merge(&vocabulary)?;
+
+    let file = match args.next() {
+        Some(path) => std::fs::read_to_string(&path)
+            .into_diagnostic()
+            .with_context(|| format!("read input {path}"))?,
+        None => include_str!("../../input2.txt").to_string(),
+    };
+    let result = process_with(&file, &vocabulary).context("process localized part 2")?;
+    println!("{}", result);
+    Ok(())
+}
@@ -1,16 +1,8 @@
 use day_01::part1::process;
 use miette::Context;
 
-#[cfg(feature = "dhat-heap")]
-#[global_allocator]
-static ALLOC: dhat::Alloc = dhat::Alloc;
-
 #[tracing::instrument]
 fn main() -> miette::Result<()> {
-    #[cfg(feature = "dhat-heap")]
-    let _profiler = dhat::Profiler::new_heap();
-
-    #[cfg(not(feature = "dhat-heap"))]
     tracing_subscriber::fmt::init();
 
     let file = include_str!("../../input1.txt");
@@ -6,4 +6,13 @@ pub enum AocError {
     #[error(transparent)]
     #[diagnostic(code(aoc::io_error))]
     IoError(#[from] std::io::Error),
+    #[error("Bad vocabulary entry on line {0}: '{1}'")]
+    #[diagnostic(code(aoc::bad_vocabulary_entry))]
+    BadVocabularyEntry(usize, String),
+    #[error("Vocabularies disagree on the value of '{0}'")]
+    #[diagnostic(code(aoc::conflicting_vocabulary))]
+    ConflictingVocabulary(String),
+    #[error("No locale pack named '{0}'")]
+    #[diagnostic(code(aoc::unknown_locale))]
+    UnknownLocale(String),
 }
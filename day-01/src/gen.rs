@@ -0,0 +1,74 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// A random input with the answers it was built to have
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    pub input: String,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+const NUMERALS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+/// Letters found in no numeral, so filler never spells one by accident
+const FILLER: &[u8] = b"abcdjklmpqyz";
+
+/// `size` lines of digits 1 to 9 and numerals in filler, each line with a digit at least.
+pub fn generate(seed: u64, size: usize) -> Puzzle {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut input = String::new();
+    let (mut part1, mut part2) = (0u64, 0u64);
+
+    for _ in 0..size {
+        let tokens = rng.gen_range(1..=5);
+        let digit_at = rng.gen_range(0..tokens);
+        let (mut digits, mut symbols) = (vec![], vec![]);
+        let mut line = String::new();
+        for t in 0..tokens {
+            // Filler between tokens keeps neighbouring numerals from overlapping.
+            for _ in 0..rng.gen_range(usize::from(t > 0)..=3) {
+                line.push(FILLER[rng.gen_range(0..FILLER.len())] as char);
+            }
+            let value = rng.gen_range(1..=9u64);
+            if t == digit_at || rng.gen_bool(0.5) {
+                line.push_str(&value.to_string());
+                digits.push(value);
+            } else {
+                line.push_str(NUMERALS[value as usize - 1]);
+            }
+            symbols.push(value);
+        }
+        for _ in 0..rng.gen_range(0..=3) {
+            line.push(FILLER[rng.gen_range(0..FILLER.len())] as char);
+        }
+
+        part1 += 10 * digits[0] + digits[digits.len() - 1];
+        part2 += 10 * symbols[0] + symbols[symbols.len() - 1];
+        input.push_str(&line);
+        input.push('\n');
+    }
+
+    Puzzle {
+        input,
+        part1: Some(part1.to_string()),
+        part2: Some(part2.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate() -> miette::Result<()> {
+        for seed in 0..20 {
+            let puzzle = generate(seed, 50);
+            assert_eq!(puzzle, generate(seed, 50));
+            assert_eq!(puzzle.part1.unwrap(), crate::part1::process(&puzzle.input)?);
+            assert_eq!(puzzle.part2.unwrap(), crate::part2::process(&puzzle.input)?);
+        }
+        Ok(())
+    }
+}
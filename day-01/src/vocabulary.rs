@@ -0,0 +1,187 @@
+use std::ops::RangeInclusive;
+
+use nom::{
+    bytes::complete::take_till1,
+    character::complete::{self, space0, space1},
+    sequence::{delimited, separated_pair},
+    IResult,
+};
+
+use crate::custom_error::AocError;
+
+/// Locale packs shipped with the crate as `(name, vocabulary text)`
+const LOCALES: [(&str, &str); 4] = [
+    ("en", include_str!("../locales/en.txt")),
+    ("de", include_str!("../locales/de.txt")),
+    ("fr", include_str!("../locales/fr.txt")),
+    ("roman", include_str!("../locales/roman.txt")),
+];
+
+/// A table of words (numerals, digits...) standing for the values 0 to 9
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Vocabulary {
+    // Kept sorted by decreasing word length, so the first match is the longest.
+    entries: Vec<(String, u8)>,
+}
+
+impl Vocabulary {
+    /// The ASCII digits 0 to 9
+    pub fn digits() -> Self {
+        let mut vocabulary = Self::default();
+        for value in 0..=9u8 {
+            vocabulary.insert(value.to_string(), value);
+        }
+        vocabulary
+    }
+
+    /// Look up one of the shipped locale packs: en, de, fr or roman
+    pub fn locale(name: &str) -> Result<Self, AocError> {
+        let (_, text) = LOCALES
+            .iter()
+            .find(|(locale, _)| *locale == name)
+            .ok_or_else(|| AocError::UnknownLocale(name.to_string()))?;
+        Self::parse(text)
+    }
+
+    /// Names of the shipped locale packs
+    pub fn locales() -> impl Iterator<Item = &'static str> {
+        LOCALES.iter().map(|(name, _)| *name)
+    }
+
+    /// Parse a vocabulary from text like:
+    ///
+    /// ```text
+    /// # Comments and blank lines are ignored
+    /// one   1
+    /// two   2
+    /// ```
+    ///
+    /// i.e. one word and its value (0 to 9) per line, every word listed once.
+    pub fn parse(text: &str) -> Result<Self, AocError> {
+        let mut vocabulary = Self::default();
+
+        for (no, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+            let bad_entry = || AocError::BadVocabularyEntry(no + 1, line.to_string());
+
+            let (rest, (word, value)) = entry(line).map_err(|_| bad_entry())?;
+            if !rest.is_empty() || value > 9 {
+                return Err(bad_entry());
+            }
+            if vocabulary.value_of(word).is_some() {
+                return Err(bad_entry());
+            }
+            vocabulary.insert(word.to_string(), value);
+        }
+        Ok(vocabulary)
+    }
+
+    /// Add all entries of `other` to this vocabulary. Words known to both must agree on their
+    /// value.
+    pub fn merge(mut self, other: &Vocabulary) -> Result<Self, AocError> {
+        for (word, value) in &other.entries {
+            match self.value_of(word) {
+                Some(known) if known != *value => {
+                    return Err(AocError::ConflictingVocabulary(word.clone()))
+                }
+                Some(_) => {}
+                None => self.insert(word.clone(), *value),
+            }
+        }
+        Ok(self)
+    }
+
+    /// Keep only words for values in `range`, e.g. `1..=9` for the original puzzle.
+    pub fn restricted_to(mut self, range: RangeInclusive<u8>) -> Self {
+        self.entries.retain(|(_, value)| range.contains(value));
+        self
+    }
+
+    /// Concatenate leftmost and rightmost symbol in line. The leftmost symbol is the one
+    /// starting first, the rightmost the one ending last; where symbols overlap like in `VIII`
+    /// the longer one wins. Returns `None` if the line holds no symbol at all.
+    pub fn calibration_value(&self, line: &str) -> Option<u32> {
+        let line = line.as_bytes();
+
+        let lms = (0..line.len()).find_map(|l| {
+            self.entries
+                .iter()
+                .find(|(word, _)| line[l..].starts_with(word.as_bytes()))
+        })?;
+        let rms = (0..=line.len()).rev().find_map(|r| {
+            self.entries
+                .iter()
+                .find(|(word, _)| line[..r].ends_with(word.as_bytes()))
+        })?;
+
+        Some(10 * lms.1 as u32 + rms.1 as u32)
+    }
+
+    fn value_of(&self, word: &str) -> Option<u8> {
+        self.entries
+            .iter()
+            .find(|(known, _)| known == word)
+            .map(|(_, value)| *value)
+    }
+
+    fn insert(&mut self, word: String, value: u8) {
+        let at = self
+            .entries
+            .partition_point(|(known, _)| known.len() >= word.len());
+        self.entries.insert(at, (word, value));
+    }
+}
+
+/// Parse a single `word value` entry
+fn entry(i: &str) -> IResult<&str, (&str, u8)> {
+    delimited(
+        space0,
+        separated_pair(
+            take_till1(|c: char| c.is_whitespace()),
+            space1,
+            complete::u8,
+        ),
+        space0,
+    )(i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locales() -> miette::Result<()> {
+        for name in Vocabulary::locales() {
+            assert_eq!(10, Vocabulary::locale(name)?.entries.len(), "{name}");
+        }
+        assert!(Vocabulary::locale("tlh").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Vocabulary::parse("# fine\n\nten 10").is_err());
+        assert!(Vocabulary::parse("one 1\none 2").is_err());
+        assert!(Vocabulary::parse("one 1\none 1").is_err());
+        assert!(Vocabulary::parse("one").is_err());
+        assert!(Vocabulary::parse("one 1 uno").is_err());
+    }
+
+    #[test]
+    fn test_calibration_value() -> miette::Result<()> {
+        let german = Vocabulary::locale("de")?.merge(&Vocabulary::digits())?;
+        assert_eq!(Some(51), german.calibration_value("xfünfzwei3einsx"));
+        assert_eq!(Some(0), german.calibration_value("nullacht0"));
+
+        let french = Vocabulary::locale("fr")?;
+        assert_eq!(Some(97), french.calibration_value("neufzérosept"));
+        assert_eq!(None, french.calibration_value("12"));
+
+        let roman = Vocabulary::locale("roman")?;
+        assert_eq!(Some(48), roman.calibration_value("xIVxxVIII"));
+        assert_eq!(Some(99), roman.calibration_value("IX"));
+        Ok(())
+    }
+}
@@ -1,20 +1,20 @@
-use day_02::part1::process;
-use miette::Context;
-
-#[cfg(feature = "dhat-heap")]
-#[global_allocator]
-static ALLOC: dhat::Alloc = dhat::Alloc;
+use day_02::part1::{process_with_bag, BAG};
+use miette::{bail, miette, Context};
 
 #[tracing::instrument]
 fn main() -> miette::Result<()> {
-    #[cfg(feature = "dhat-heap")]
-    let _profiler = dhat::Profiler::new_heap();
-
-    #[cfg(not(feature = "dhat-heap"))]
     tracing_subscriber::fmt::init();
 
+    // `--bag '12 red, 13 green, 14 blue'` overrides the puzzle's bag.
+    let mut args = std::env::args().skip(1);
+    let bag = match args.next().as_deref() {
+        Some("--bag") => args.next().ok_or_else(|| miette!("--bag needs a value"))?,
+        Some(other) => bail!("Unknown argument '{other}', try --bag '{BAG}'"),
+        None => BAG.to_string(),
+    };
+
     let file = include_str!("../../input1.txt");
-    let result = process(file).context("process part 1")?;
+    let result = process_with_bag(file, &bag.parse()?).context("process part 1")?;
     println!("{}", result);
     Ok(())
 }
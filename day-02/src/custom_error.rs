@@ -6,4 +6,22 @@ pub enum AocError {
     #[error(transparent)]
     #[diagnostic(code(aoc::io_error))]
     IoError(#[from] std::io::Error),
+    #[error("Line should look like 'Game n: ...', got '{0}'")]
+    #[diagnostic(code(aoc::bad_game))]
+    BadGame(String),
+    #[error("Bag should look like '12 red, 13 green, 14 blue', got '{0}'")]
+    #[diagnostic(code(aoc::bad_bag))]
+    BadBag(String),
+    #[error("Game {0} draws {1} cubes, but the bag holds no such color")]
+    #[diagnostic(
+        code(aoc::color_not_in_bag),
+        help("add the color to the bag, e.g. --bag '12 red, 13 green, 14 blue, 0 {1}'")
+    )]
+    ColorNotInBag(u32, String),
+    #[error("Draw holds too many {0} cubes to count")]
+    #[diagnostic(code(aoc::too_many_cubes))]
+    TooManyCubes(String),
+    #[error("Power of a game's minimum bag doesn't fit 64 bits")]
+    #[diagnostic(code(aoc::power_overflow))]
+    PowerOverflow,
 }
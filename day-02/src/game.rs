@@ -0,0 +1,353 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    ops::Add,
+    str::FromStr,
+};
+
+use nom::{
+    bytes::complete::tag,
+    character::complete::{self, alpha1},
+    combinator::all_consuming,
+    multi::separated_list1,
+    sequence::{delimited, pair, separated_pair},
+    IResult, Parser,
+};
+
+use crate::custom_error::AocError;
+
+/// A draw (aka subset) in a game, also used to describe the bag's contents. Colors are whatever
+/// the input names, a color missing from the map counts zero cubes.
+#[derive(Default, Debug, Clone, Eq)]
+pub struct Draw {
+    cubes: BTreeMap<String, u32>, // Number of cubes by color
+}
+
+impl Draw {
+    /// Number of cubes of `color` in this draw
+    pub fn get(&self, color: &str) -> u32 {
+        self.cubes.get(color).copied().unwrap_or(0)
+    }
+
+    /// Colors named by this draw, even when named with zero cubes
+    pub fn colors(&self) -> impl Iterator<Item = &str> {
+        self.cubes.keys().map(|color| color.as_str())
+    }
+
+    /// Max every internal value by comparing self and other and return result as `Draw`
+    pub fn max(&self, other: &Draw) -> Self {
+        let mut max = self.clone();
+        for (color, n) in &other.cubes {
+            let count = max.cubes.entry(color.clone()).or_default();
+            *count = (*count).max(*n);
+        }
+        max
+    }
+
+    /// Return the internal values of all `colors` multiplied
+    pub fn pow<'c>(&self, colors: impl IntoIterator<Item = &'c str>) -> Result<u64, AocError> {
+        colors.into_iter().try_fold(1u64, |pow, color| {
+            pow.checked_mul(self.get(color) as u64)
+                .ok_or(AocError::PowerOverflow)
+        })
+    }
+
+    /// Cube counts one color at a time
+    pub fn cubes(&self) -> impl Iterator<Item = Ncubes> + '_ {
+        self.cubes.iter().map(|(color, n)| Ncubes {
+            count: *n,
+            color: color.clone(),
+        })
+    }
+
+    /// Add up cubes the way a draw lists them, a color may come more than once.
+    fn from_cubes(cubes: Vec<Ncubes>) -> Result<Self, AocError> {
+        cubes
+            .into_iter()
+            .try_fold(Draw::default(), |draw, n| draw + n)
+    }
+
+    fn union<'d>(&'d self, other: &'d Draw) -> BTreeSet<&'d str> {
+        self.colors().chain(other.colors()).collect()
+    }
+}
+
+/// Build a draw like `[("red", 12), ("green", 13), ("blue", 14)].into_iter().collect()`, a
+/// color given twice keeps its last count.
+impl<'c> FromIterator<(&'c str, u32)> for Draw {
+    fn from_iter<T: IntoIterator<Item = (&'c str, u32)>>(iter: T) -> Self {
+        Draw {
+            cubes: iter
+                .into_iter()
+                .map(|(color, count)| (color.to_string(), count))
+                .collect(),
+        }
+    }
+}
+
+/// Parse a bag from text like `12 red, 13 green, 14 blue`
+impl FromStr for Draw {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (_, cubes) =
+            all_consuming(draw)(s.trim()).map_err(|_| AocError::BadBag(s.to_string()))?;
+        Draw::from_cubes(cubes)
+    }
+}
+
+/// Draws are equal if they hold the same number of cubes of every color.
+impl PartialEq for Draw {
+    fn eq(&self, other: &Self) -> bool {
+        self.union(other)
+            .into_iter()
+            .all(|color| self.get(color) == other.get(color))
+    }
+}
+
+/// Draws compare color by color, so `draw <= bag` holds if the bag could have produced the draw.
+/// Draws with more of one color and less of another are not comparable.
+impl PartialOrd for Draw {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.union(other)
+            .into_iter()
+            .map(|color| self.get(color).cmp(&other.get(color)))
+            .try_fold(Ordering::Equal, |acc, ord| match (acc, ord) {
+                (Ordering::Equal, ord) => Some(ord),
+                (acc, Ordering::Equal) => Some(acc),
+                (acc, ord) if acc == ord => Some(acc),
+                _ => None,
+            })
+    }
+}
+
+/// `draw = (draw + number_of_cubes)?;` is neat feature for our API here.
+impl Add<Ncubes> for Draw {
+    type Output = Result<Self, AocError>;
+
+    fn add(mut self, rhs: Ncubes) -> Self::Output {
+        match self.get(&rhs.color).checked_add(rhs.count) {
+            Some(count) => {
+                self.cubes.insert(rhs.color, count);
+                Ok(self)
+            }
+            None => Err(AocError::TooManyCubes(rhs.color)),
+        }
+    }
+}
+
+/// Parser's representation of cubes; number of... with a color
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ncubes {
+    pub count: u32,
+    pub color: String,
+}
+
+impl Display for Ncubes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.count, self.color)
+    }
+}
+
+/// Why a game is impossible: a draw showed more cubes of a color than the bag holds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub draw: usize, // Index of the offending draw
+    pub drawn: Ncubes,
+    pub in_bag: u32,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "draw {} shows {} cubes, bag holds {}",
+            self.draw + 1,
+            self.drawn,
+            self.in_bag
+        )
+    }
+}
+
+/// A game as recorded on one line of input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub id: u32,
+    pub draws: Vec<Draw>,
+}
+
+impl Game {
+    /// Are all draws possible when checked against a draw emptying the bag? Fails if the game
+    /// names a color the bag knows nothing about.
+    pub fn is_possible_with(&self, bag: &Draw) -> Result<bool, AocError> {
+        self.check_colors(bag)?;
+        Ok(self.draws.iter().all(|draw| draw <= bag))
+    }
+
+    /// The minimum set of cubes that must have been present in the bag
+    pub fn min_bag(&self) -> Draw {
+        self.draws
+            .iter()
+            .fold(Draw::default(), |set, other| set.max(other))
+    }
+
+    /// Every color of every draw exceeding the bag's contents
+    pub fn violations(&self, bag: &Draw) -> Result<Vec<Violation>, AocError> {
+        self.check_colors(bag)?;
+        Ok(self
+            .draws
+            .iter()
+            .enumerate()
+            .flat_map(|(no, draw)| {
+                draw.cubes()
+                    .filter(|drawn| drawn.count > bag.get(&drawn.color))
+                    .map(move |drawn| Violation {
+                        draw: no,
+                        in_bag: bag.get(&drawn.color),
+                        drawn,
+                    })
+            })
+            .collect())
+    }
+
+    fn check_colors(&self, bag: &Draw) -> Result<(), AocError> {
+        let known = bag.colors().collect::<BTreeSet<_>>();
+        match self
+            .draws
+            .iter()
+            .flat_map(|draw| draw.colors())
+            .find(|color| !known.contains(color))
+        {
+            Some(color) => Err(AocError::ColorNotInBag(self.id, color.to_string())),
+            None => Ok(()),
+        }
+    }
+}
+
+/// All colors named anywhere in `games`
+pub fn palette(games: &[Game]) -> BTreeSet<&str> {
+    games
+        .iter()
+        .flat_map(|game| game.draws.iter().flat_map(|draw| draw.colors()))
+        .collect()
+}
+
+/// Parse all games, one per line
+pub fn parse_games(input: &str) -> Result<Vec<Game>, AocError> {
+    input
+        .lines()
+        .map(|line| {
+            let (_, (id, draws)) =
+                all_consuming(game)(line).map_err(|_| AocError::BadGame(line.to_string()))?;
+            let draws = draws
+                .into_iter()
+                .map(Draw::from_cubes)
+                .collect::<Result<_, _>>()?;
+            Ok(Game { id, draws })
+        })
+        .collect()
+}
+
+/// Parse a line like `Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green` into its id and
+/// the cubes of every draw
+fn game(i: &str) -> IResult<&str, (u32, Vec<Vec<Ncubes>>)> {
+    pair(
+        delimited(tag("Game "), complete::u32, tag(": ")),
+        separated_list1(tag("; "), draw),
+    )(i)
+}
+
+/// Parse the cubes of a single draw from raw data
+fn draw(i: &str) -> IResult<&str, Vec<Ncubes>> {
+    separated_list1(tag(", "), cubes)(i)
+}
+
+fn cubes(i: &str) -> IResult<&str, Ncubes> {
+    separated_pair(complete::u32, tag(" "), alpha1)
+        .map(|(count, color): (u32, &str)| Ncubes {
+            count,
+            color: color.to_string(),
+        })
+        .parse(i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgb(red: u32, green: u32, blue: u32) -> Draw {
+        [("red", red), ("green", green), ("blue", blue)]
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_game() -> miette::Result<()> {
+        let games = parse_games("Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green")?;
+        assert_eq!(
+            vec![Game {
+                id: 3,
+                draws: vec![rgb(20, 8, 6), rgb(4, 13, 5)]
+            }],
+            games
+        );
+        assert!(parse_games("Game 3: 8 green, 6 purple").is_ok());
+        assert!(parse_games("Game 3: 8 green, 6 ").is_err());
+        assert!(parse_games("Game 3 8 green").is_err());
+        // Counts of one colour adding up past `u32::MAX` are an error
+        assert!(parse_games("Game 1: 4294967295 red, 1 red").is_err());
+        assert_eq!(rgb(5, 0, 0), "2 red, 3 red".parse()?);
+        assert_eq!(rgb(12, 13, 14), "12 red, 13 green, 14 blue".parse()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_draw_order() {
+        let bag = rgb(12, 13, 14);
+        assert!(rgb(12, 0, 14) <= bag);
+        assert!(rgb(13, 13, 14) > bag);
+        assert_eq!(None, rgb(13, 0, 0).partial_cmp(&bag));
+        assert_eq!(rgb(1, 0, 0), [("red", 1)].into_iter().collect());
+    }
+
+    #[test]
+    fn test_violations() -> miette::Result<()> {
+        let bag = rgb(12, 13, 14);
+        let game = &parse_games("Game 4: 1 green, 3 red, 6 blue; 3 green, 15 blue, 14 red")?[0];
+
+        assert!(!game.is_possible_with(&bag)?);
+        assert_eq!(rgb(14, 3, 15), game.min_bag());
+        assert_eq!(
+            vec![
+                Violation {
+                    draw: 1,
+                    drawn: Ncubes {
+                        count: 15,
+                        color: "blue".to_string()
+                    },
+                    in_bag: 14
+                },
+                Violation {
+                    draw: 1,
+                    drawn: Ncubes {
+                        count: 14,
+                        color: "red".to_string()
+                    },
+                    in_bag: 12
+                },
+            ],
+            game.violations(&bag)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_color_not_in_bag() -> miette::Result<()> {
+        let games = parse_games("Game 7: 2 red, 1 purple; 3 teal")?;
+        assert_eq!(BTreeSet::from(["purple", "red", "teal"]), palette(&games));
+        assert!(games[0].is_possible_with(&rgb(12, 13, 14)).is_err());
+        assert!(games[0].is_possible_with(&"2 red, 1 purple, 3 teal".parse()?)?);
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+/// A random input with the answers it was built to have
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    pub input: String,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+const COLORS: [&str; 3] = ["red", "green", "blue"];
+
+/// The bag of part 1, in the order of `COLORS`
+const BAG: [u32; 3] = [12, 13, 14];
+
+/// `size` games of one to six draws, with up to 20 cubes of a color in a draw.
+pub fn generate(seed: u64, size: usize) -> Puzzle {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut input = String::new();
+    let mut part1 = 0;
+    let mut min_bags = vec![];
+
+    for id in 1..=size {
+        let mut min_bag = BTreeMap::new();
+        let mut draws = vec![];
+        for _ in 0..rng.gen_range(1..=6) {
+            let mut colors = (0..COLORS.len()).collect::<Vec<_>>();
+            colors.shuffle(&mut rng);
+            colors.truncate(rng.gen_range(1..=COLORS.len()));
+            let cubes = colors
+                .into_iter()
+                .map(|color| {
+                    let count = rng.gen_range(1..=20u32);
+                    let max = min_bag.entry(color).or_insert(0);
+                    *max = count.max(*max);
+                    format!("{count} {}", COLORS[color])
+                })
+                .collect::<Vec<_>>();
+            draws.push(cubes.join(", "));
+        }
+
+        if min_bag.iter().all(|(color, count)| *count <= BAG[*color]) {
+            part1 += id;
+        }
+        input.push_str(&format!("Game {id}: {}\n", draws.join("; ")));
+        min_bags.push(min_bag);
+    }
+
+    // Colors no game draws are left out of the powers, colors some game misses count zero.
+    let palette = min_bags
+        .iter()
+        .flat_map(|bag| bag.keys().copied())
+        .collect::<BTreeSet<_>>();
+    let part2: u64 = min_bags
+        .iter()
+        .map(|bag| {
+            palette
+                .iter()
+                .map(|color| *bag.get(color).unwrap_or(&0) as u64)
+                .product::<u64>()
+        })
+        .sum();
+
+    Puzzle {
+        input,
+        part1: Some(part1.to_string()),
+        part2: Some(part2.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate() -> miette::Result<()> {
+        for seed in 0..20 {
+            let puzzle = generate(seed, 50);
+            assert_eq!(puzzle.part1.unwrap(), crate::part1::process(&puzzle.input)?);
+            assert_eq!(puzzle.part2.unwrap(), crate::part2::process(&puzzle.input)?);
+        }
+        Ok(())
+    }
+}
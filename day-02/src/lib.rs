@@ -1,4 +1,6 @@
 pub mod custom_error;
 
+pub mod game;
+pub mod gen;
 pub mod part1;
 pub mod part2;
@@ -1,148 +1,32 @@
-use std::ops::Add;
+use tracing::info_span;
 
-use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::digit1,
-    multi::many0,
-    sequence::{delimited, preceded, tuple},
-    IResult,
+use crate::{
+    custom_error::AocError,
+    game::{parse_games, Draw},
 };
 
-use crate::custom_error::AocError;
+/// The bag's contents as given by the puzzle
+pub const BAG: &str = "12 red, 13 green, 14 blue";
 
-/// A draw (aka subset) in a game
-#[derive(Default, Debug)]
-struct Draw {
-    red: u32,   // Number of red cubes in draw
-    green: u32, // Number of green cubes in draw
-    blue: u32,  // Number of blue cubes in draw
-}
-
-impl Draw {
-    fn new(nred: u32, ngreen: u32, nblue: u32) -> Self {
-        Self {
-            red: nred,
-            green: ngreen,
-            blue: nblue,
-        }
-    }
-
-    /// Is this greater or equal than `other`
-    fn ge(&self, other: Draw) -> bool {
-        self.red >= other.red && self.green >= other.green && self.blue >= other.blue
-    }
+#[tracing::instrument(skip(input))]
+pub fn process(input: &str) -> miette::Result<String, AocError> {
+    process_with_bag(input, &BAG.parse()?)
 }
 
-/// `draw = draw + number_of_cubes_red;` (etc.) is neat feature for our API here.
-impl Add<Ncubes> for Draw {
-    type Output = Self;
-
-    fn add(self, rhs: Ncubes) -> Self {
-        let (r, g, b) = match rhs {
-            Ncubes::Red(r) => (r, 0, 0),
-            Ncubes::Green(g) => (0, g, 0),
-            Ncubes::Blue(b) => (0, 0, b),
-        };
-
-        Self {
-            red: self.red + r,
-            green: self.green + g,
-            blue: self.blue + b,
+/// Sum the ids of all games possible with `bag`, whatever its colors.
+pub fn process_with_bag(input: &str, bag: &Draw) -> miette::Result<String, AocError> {
+    let games = info_span!("parse").in_scope(|| parse_games(input))?;
+    let _solve = info_span!("solve").entered();
+    let mut sum_ids = 0u32;
+    for game in games {
+        if game.is_possible_with(bag)? {
+            sum_ids += game.id;
         }
     }
-}
-
-#[tracing::instrument]
-pub fn process(input: &str) -> miette::Result<String, AocError> {
-    let bag = Draw::new(12, 13, 14);
-
-    let sum_ids: u32 = input
-        .lines()
-        .filter_map(|line| {
-            let (draws, id) = parse_game(line).expect("Line should start with Game n: ...");
-            if all_draws_possible(draws, &bag) {
-                Some(id)
-            } else {
-                None
-            }
-        })
-        .sum();
 
     Ok(sum_ids.to_string())
 }
 
-/// Parse start of line, then return draws and game ID
-fn parse_game(i: &str) -> IResult<&str, u32> {
-    let (i, (_, id, _)) = tuple((tag("Game "), digit1, tag(":")))(i)?;
-
-    let id = u32::from_str_radix(id, 10).unwrap(); // Result guaranteed by nom
-
-    Ok((i, id))
-}
-
-/// Are all draws possible when checked against a draw emptying the bag?
-fn all_draws_possible(i: &str, bag: &Draw) -> bool {
-    let mut possible = true;
-
-    let (_, (draw, many0_draws)) = tuple((draw, many0(preceded(tag(";"), draw))))(i).unwrap();
-
-    possible = possible && bag.ge(draw);
-    for draw in many0_draws {
-        possible = possible && bag.ge(draw);
-    }
-    possible
-}
-
-/// Parse draws
-fn _parse_draws(i: &str) -> IResult<&str, Vec<Draw>> {
-    let mut draws: Vec<Draw> = vec![];
-
-    let (_, (draw, many0_draws)) = tuple((draw, many0(preceded(tag(";"), draw))))(i)?;
-
-    draws.push(draw);
-    for draw in many0_draws {
-        draws.push(draw);
-    }
-    Ok(("", draws))
-}
-
-/// Parse into a single `Draw` from raw data
-fn draw(i: &str) -> IResult<&str, Draw> {
-    let mut draw = Draw::default();
-
-    let (i, (cubes, many0_cubes)) = tuple((cubes, many0(preceded(tag(","), cubes))))(i)?;
-
-    draw = draw + cubes;
-    for cubes in many0_cubes {
-        draw = draw + cubes;
-    }
-    Ok((i, draw))
-}
-
-/// Parser's representation of cubes; number of... with a color variant
-enum Ncubes {
-    Red(u32),
-    Green(u32),
-    Blue(u32),
-}
-
-fn cubes(i: &str) -> IResult<&str, Ncubes> {
-    let (i, (n, label)) = tuple((
-        delimited(tag(" "), digit1, tag(" ")),
-        alt((tag("red"), tag("green"), tag("blue"))),
-    ))(i)?;
-
-    let n = u32::from_str_radix(n, 10).unwrap(); // Result guaranteed by nom
-
-    let cube = match label {
-        "red" => Ncubes::Red(n),
-        "green" => Ncubes::Green(n),
-        _ => Ncubes::Blue(n), // Parser guarantees again
-    };
-    Ok((i, cube))
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -157,4 +41,15 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
         assert_eq!("8", process(input)?);
         Ok(())
     }
+
+    #[test]
+    fn test_process_with_bag() -> miette::Result<()> {
+        let input = "Game 1: 3 cyan, 4 magenta; 1 yellow
+Game 2: 5 cyan, 1 key
+Game 3: 2 magenta, 2 yellow";
+        let bag = "4 cyan, 4 magenta, 4 yellow, 4 key".parse()?;
+        assert_eq!("4", process_with_bag(input, &bag)?);
+        assert!(process_with_bag(input, &"4 cyan, 4 magenta, 4 yellow".parse()?).is_err());
+        Ok(())
+    }
 }
@@ -1,123 +1,25 @@
-use std::ops::Add;
+use tracing::info_span;
 
-use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete,
-    multi::separated_list1,
-    sequence::{delimited, separated_pair},
-    IResult,
+use crate::{
+    custom_error::AocError,
+    game::{palette, parse_games},
 };
 
-use crate::custom_error::AocError;
-
-/// A draw (aka subset) in a game
-#[derive(Default, Debug)]
-struct Draw {
-    red: u32,   // Number of red cubes in draw
-    green: u32, // Number of green cubes in draw
-    blue: u32,  // Number of blue cubes in draw
-}
-
-impl Draw {
-    /// Max every internal value by comparing self and other and return result as `Draw`
-    fn max(&self, other: Draw) -> Self {
-        Self {
-            red: self.red.max(other.red),
-            green: self.green.max(other.green),
-            blue: self.blue.max(other.blue),
-        }
-    }
-
-    /// Return all internal values multiplied
-    fn pow(&self) -> u32 {
-        self.red * self.green * self.blue
-    }
-}
-
-/// `draw = draw + number_of_cubes_red;` (etc.) is neat feature for our API here.
-impl Add<ColorCount> for Draw {
-    type Output = Self;
-
-    fn add(self, rhs: ColorCount) -> Self {
-        let (r, g, b) = match rhs {
-            ColorCount::Red(r) => (r, 0, 0),
-            ColorCount::Green(g) => (0, g, 0),
-            ColorCount::Blue(b) => (0, 0, b),
-        };
-
-        Self {
-            red: self.red + r,
-            green: self.green + g,
-            blue: self.blue + b,
-        }
-    }
-}
-
-#[tracing::instrument]
+#[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String, AocError> {
-    let sum_ids: u32 = input
-        .lines()
-        .map(|line| {
-            let (draws, _) = parse_game(line).expect("Line should start with Game n: ...");
-            let (_, pow) = get_min_set_power(draws).expect("Error parsing draws");
-            pow
-        })
-        .sum();
+    let games = info_span!("parse").in_scope(|| parse_games(input))?;
+    let _solve = info_span!("solve").entered();
+    // Colors a game never draws still count, with zero cubes, when taking powers.
+    let colors = palette(&games);
 
-    Ok(sum_ids.to_string())
-}
+    // For every game, find the minimum set of cubes that must have been present
+    // and sum up its power (as the product of all colors' counts)
+    let sum_powers = games.iter().try_fold(0u64, |sum, game| {
+        sum.checked_add(game.min_bag().pow(colors.iter().copied())?)
+            .ok_or(AocError::PowerOverflow)
+    })?;
 
-/// Parse start of line, then return draws and game ID
-fn parse_game(i: &str) -> IResult<&str, u32> {
-    Ok(delimited(tag("Game "), complete::u32, tag(": "))(i)?)
-}
-
-/// For this game, find the minimum set of cubes that must have been present
-/// and return its power (as nred * ngreen * nblue)
-fn get_min_set_power(i: &str) -> IResult<&str, u32> {
-    let mut set = Draw::default();
-
-    let (_, sets) = separated_list1(tag("; "), draw)(i)?;
-
-    for other in sets {
-        set = set.max(other)
-    }
-    Ok(("", set.pow()))
-}
-
-/// Parse into a single `Draw` from raw data
-fn draw(i: &str) -> IResult<&str, Draw> {
-    let mut draw = Draw::default();
-
-    let (i, color_counts) = separated_list1(tag(", "), color_item)(i)?;
-
-    for count in color_counts {
-        draw = draw + count;
-    }
-    Ok((i, draw))
-}
-
-/// Parser's representation of cubes; number of... with a color variant
-enum ColorCount {
-    Red(u32),
-    Green(u32),
-    Blue(u32),
-}
-
-fn color_item(i: &str) -> IResult<&str, ColorCount> {
-    let (i, (n, label)) = separated_pair(
-        complete::u32,
-        tag(" "),
-        alt((tag("red"), tag("green"), tag("blue"))),
-    )(i)?;
-
-    let color_count = match label {
-        "red" => ColorCount::Red(n),
-        "green" => ColorCount::Green(n),
-        _ => ColorCount::Blue(n), // Parser guarantees again
-    };
-    Ok((i, color_count))
+    Ok(sum_powers.to_string())
 }
 
 #[cfg(test)]
@@ -135,4 +37,14 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
         assert_eq!("2286", process(input)?);
         Ok(())
     }
+
+    /// Powers past 32 bits fit, powers past 64 bits are an error
+    #[test]
+    fn test_large_power() -> miette::Result<()> {
+        let input = "Game 1: 100000 red, 100000 green, 100000 blue";
+        assert_eq!("1000000000000000", process(input)?);
+        let input = "Game 1: 4294967295 red, 4294967295 green, 4294967295 blue";
+        assert!(process(input).is_err());
+        Ok(())
+    }
 }
@@ -6,4 +6,10 @@ pub enum AocError {
     #[error(transparent)]
     #[diagnostic(code(aoc::io_error))]
     IoError(#[from] std::io::Error),
+    #[error("Schematic line {0} is {1} cells wide, expected {2}")]
+    #[diagnostic(code(aoc::ragged_schematic))]
+    RaggedSchematic(usize, usize, usize),
+    #[error("Schematic line {0} has number {1}, too large")]
+    #[diagnostic(code(aoc::number_too_large))]
+    NumberTooLarge(usize, String),
 }
@@ -0,0 +1,94 @@
+use std::collections::BTreeMap;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// A random input with the answers it was built to have
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    pub input: String,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+const SYMBOLS: &[u8] = b"#$%&*+-/=@";
+
+/// A `size` by `size` schematic of numbers up to three digits and symbols, a third of them
+/// stars. Answers are found by looking around every number placed.
+pub fn generate(seed: u64, size: usize) -> Puzzle {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut grid = vec![vec![b'.'; size]; size];
+
+    // Numbers as (value, row, first col, last col), a dot at least between two on a row.
+    let mut numbers = vec![];
+    for (row, line) in grid.iter_mut().enumerate() {
+        let mut col = rng.gen_range(0..4);
+        while col < size {
+            let digits = rng.gen_range(1..=3).min(size - col);
+            let value = rng.gen_range(10u32.pow(digits as u32 - 1)..10u32.pow(digits as u32));
+            line[col..col + digits].copy_from_slice(value.to_string().as_bytes());
+            numbers.push((value, row, col, col + digits - 1));
+            col += digits + rng.gen_range(1..8);
+        }
+    }
+    for _ in 0..size * size / 8 {
+        let (row, col) = (rng.gen_range(0..size), rng.gen_range(0..size));
+        if grid[row][col] == b'.' {
+            grid[row][col] = match rng.gen_bool(1.0 / 3.0) {
+                true => b'*',
+                false => SYMBOLS[rng.gen_range(0..SYMBOLS.len())],
+            };
+        }
+    }
+
+    let mut part1 = 0u64;
+    let mut stars = BTreeMap::<(usize, usize), Vec<u64>>::new();
+    for (value, row, first, last) in numbers {
+        let mut is_part = false;
+        #[allow(clippy::needless_range_loop)]
+        for r in row.saturating_sub(1)..=(row + 1).min(size - 1) {
+            for c in first.saturating_sub(1)..=(last + 1).min(size - 1) {
+                let b = grid[r][c];
+                if b != b'.' && !b.is_ascii_digit() {
+                    is_part = true;
+                }
+                if b == b'*' {
+                    stars.entry((r, c)).or_default().push(value as u64);
+                }
+            }
+        }
+        if is_part {
+            part1 += value as u64;
+        }
+    }
+    let part2: u64 = stars
+        .values()
+        .filter(|numbers| numbers.len() == 2)
+        .map(|numbers| numbers[0] * numbers[1])
+        .sum();
+
+    let mut input = String::new();
+    for line in grid {
+        input.push_str(&String::from_utf8_lossy(&line));
+        input.push('\n');
+    }
+    Puzzle {
+        input,
+        part1: Some(part1.to_string()),
+        part2: Some(part2.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate() -> miette::Result<()> {
+        for seed in 0..20 {
+            let puzzle = generate(seed, 40);
+            assert_eq!(puzzle.part1.unwrap(), crate::part1::process(&puzzle.input)?);
+            assert_eq!(puzzle.part2.unwrap(), crate::part2::process(&puzzle.input)?);
+        }
+        Ok(())
+    }
+}
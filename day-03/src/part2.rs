@@ -1,8 +1,33 @@
-use crate::custom_error::AocError;
+use std::collections::BTreeMap;
 
-#[tracing::instrument]
-pub fn process(_input: &str) -> miette::Result<String, AocError> {
-    todo!("day part 2");
+use tracing::info_span;
+
+use crate::{custom_error::AocError, schematic::Schematic};
+
+#[tracing::instrument(skip(input))]
+pub fn process(input: &str) -> miette::Result<String, AocError> {
+    let schematic = info_span!("parse").in_scope(|| Schematic::parse(input))?;
+    let _solve = info_span!("solve").entered();
+
+    // Collect the numbers next to every '*', keyed by the star's (row, col)
+    let mut stars: BTreeMap<(usize, usize), Vec<u32>> = BTreeMap::new();
+    for (value, row, cols) in schematic.number_spans() {
+        for (r, c, _) in schematic
+            .neighbours(row, cols)
+            .filter(|(_, _, b)| *b == b'*')
+        {
+            stars.entry((r, c)).or_default().push(value);
+        }
+    }
+
+    // A gear is a star with exactly two numbers around it.
+    let sum: u64 = stars
+        .values()
+        .filter(|numbers| numbers.len() == 2)
+        .map(|numbers| numbers[0] as u64 * numbers[1] as u64)
+        .sum();
+
+    Ok(sum.to_string())
 }
 
 #[cfg(test)]
@@ -11,9 +36,17 @@ mod tests {
 
     #[test]
     fn test_process() -> miette::Result<()> {
-        todo!("haven't built test yet");
-        let input = "";
-        assert_eq!("", process(input)?);
+        let input = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+        assert_eq!("467835", process(input)?);
         Ok(())
     }
 }
@@ -0,0 +1,155 @@
+use std::ops::Range;
+
+use crate::custom_error::AocError;
+
+/// The engine schematic as a dense grid of bytes, row after row without line endings.
+#[derive(Debug)]
+pub struct Schematic {
+    cells: Vec<u8>,
+    width: usize,
+    height: usize,
+}
+
+impl Schematic {
+    /// Parse the input, all lines must be of equal length and numbers fit `u32`.
+    pub fn parse(input: &str) -> Result<Self, AocError> {
+        let width = input.lines().next().map_or(0, str::len);
+        let mut cells = Vec::with_capacity(input.len());
+        let mut height = 0;
+
+        for (row, line) in input.lines().enumerate() {
+            if line.len() != width {
+                return Err(AocError::RaggedSchematic(row + 1, line.len(), width));
+            }
+            if let Some(number) = line
+                .split(|c: char| !c.is_ascii_digit())
+                .find(|number| !number.is_empty() && number.parse::<u32>().is_err())
+            {
+                return Err(AocError::NumberTooLarge(row + 1, number.to_string()));
+            }
+            cells.extend_from_slice(line.as_bytes());
+            height += 1;
+        }
+
+        Ok(Self {
+            cells,
+            width,
+            height,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The byte at (row, col), `None` outside the grid
+    pub fn get(&self, row: usize, col: usize) -> Option<u8> {
+        (row < self.height && col < self.width).then(|| self.cells[row * self.width + col])
+    }
+
+    /// All numbers in reading order as `(value, row, col_range)`. Numbers never wrap to the
+    /// next row.
+    pub fn number_spans(&self) -> impl Iterator<Item = (u32, usize, Range<usize>)> + '_ {
+        self.cells
+            .chunks(self.width.max(1))
+            .enumerate()
+            .flat_map(|(row, line)| {
+                let mut col = 0;
+                std::iter::from_fn(move || {
+                    // Skip to the next digit, then take all digits following.
+                    let start = col + line[col..].iter().position(u8::is_ascii_digit)?;
+                    let end = line[start..]
+                        .iter()
+                        .position(|b| !b.is_ascii_digit())
+                        .map_or(line.len(), |len| start + len);
+                    col = end;
+
+                    let value = line[start..end]
+                        .iter()
+                        .fold(0u32, |value, b| value * 10 + (b - b'0') as u32);
+                    Some((value, row, start..end))
+                })
+            })
+    }
+
+    /// All cells around a span on `row` as `(row, col, byte)`, like the p's in:
+    ///
+    /// ```text
+    ///     p p p p p
+    ///     p 1 2 3 p
+    ///     p p p p p
+    /// ```
+    ///
+    /// Cells outside the grid are left out.
+    pub fn neighbours(
+        &self,
+        row: usize,
+        cols: Range<usize>,
+    ) -> impl Iterator<Item = (usize, usize, u8)> + '_ {
+        let rows = row.saturating_sub(1)..(row + 2).min(self.height);
+        let hull = cols.start.saturating_sub(1)..(cols.end + 1).min(self.width);
+
+        rows.flat_map(move |r| hull.clone().map(move |c| (r, c)))
+            .filter(move |(r, c)| *r != row || !cols.contains(c))
+            .map(|(r, c)| (r, c, self.cells[r * self.width + c]))
+    }
+}
+
+/// Anything but digits and '.' is a symbol.
+pub fn is_symbol(b: u8) -> bool {
+    !b.is_ascii_digit() && b != b'.'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_number_spans() -> miette::Result<()> {
+        let schematic = Schematic::parse(
+            "467..114
+...*....
+35....12",
+        )?;
+        assert_eq!(
+            vec![(467, 0, 0..3), (114, 0, 5..8), (35, 2, 0..2), (12, 2, 6..8)],
+            schematic.number_spans().collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_neighbours() -> miette::Result<()> {
+        let schematic = Schematic::parse(
+            "a.b.c
+d.12e
+f.g.h",
+        )?;
+        // Hull is symmetric left and right of the span, and clipped at the right edge.
+        let hull = schematic
+            .neighbours(1, 2..4)
+            .map(|(_, _, b)| b as char)
+            .collect::<String>();
+        assert_eq!(".b.c.e.g.h", hull);
+
+        // Clipped at the top left corner
+        assert_eq!(3, schematic.neighbours(0, 0..1).count());
+        Ok(())
+    }
+
+    #[test]
+    fn test_ragged() {
+        assert!(Schematic::parse("..\n...").is_err());
+    }
+
+    /// Found by the fuzzer overflowing while reading the number
+    #[test]
+    fn test_number_too_large() {
+        assert!(Schematic::parse("22222222222").is_err());
+        assert!(Schematic::parse("4294967295*").is_ok());
+    }
+}
@@ -0,0 +1,111 @@
+use nom::{
+    self,
+    bytes::complete::tag,
+    character::complete::{self, space0, space1},
+    combinator::all_consuming,
+    multi::separated_list1,
+    sequence::{delimited, pair, preceded, separated_pair},
+    IResult,
+};
+
+use crate::custom_error::AocError;
+
+/// A scratchcard with its numbers as bitsets, bit n set for number n
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Card {
+    pub id: u32,
+    pub winning_numbers: u128,
+    pub numbers_you_have: u128,
+}
+
+impl Card {
+    /// Count numbers winning something on card's rhs (numbers you have twice count once)
+    pub fn matches(&self) -> u32 {
+        (self.winning_numbers & self.numbers_you_have).count_ones()
+    }
+
+    /// 1 match -> 2^0 or 1 point, 2 -> 2^1 or 2 points, ... 4 -> 2^3 or 8 points, etc.
+    /// At most 128 numbers match, so points fit a u128.
+    pub fn points(&self) -> u128 {
+        match self.matches() {
+            0 => 0, // no winning numbers, no points
+            count => 1 << (count - 1),
+        }
+    }
+}
+
+/// Parse all cards, one per line
+pub fn parse_cards(input: &str) -> Result<Vec<Card>, AocError> {
+    input.lines().map(parse_card).collect()
+}
+
+/// Parse a line like `Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53`
+pub fn parse_card(line: &str) -> Result<Card, AocError> {
+    let (_, (id, winning_numbers, numbers_you_have)) =
+        all_consuming(card)(line).map_err(|_| AocError::BadCard(line.to_string()))?;
+
+    Ok(Card {
+        id,
+        winning_numbers: bitset(id, &winning_numbers)?,
+        numbers_you_have: bitset(id, &numbers_you_have)?,
+    })
+}
+
+/// Result rhs holds: (card_id, winning_numbers, numbers_you_have)
+fn card(i: &str) -> IResult<&str, (u32, Vec<u32>, Vec<u32>)> {
+    let (i, card_id) = delimited(pair(tag("Card"), space1), complete::u32, tag(":"))(i)?;
+
+    // Then get what's required to eval cards.
+    let (i, (winning_numbers, numbers_you_have)) = separated_pair(
+        preceded(space1, separated_list1(space1, complete::u32)), // *Must* eat space before e.g. '30  1 29'...
+        tag(" |"),
+        delimited(space1, separated_list1(space1, complete::u32), space0),
+    )(i)?;
+
+    Ok((i, (card_id, winning_numbers, numbers_you_have)))
+}
+
+fn bitset(id: u32, numbers: &[u32]) -> Result<u128, AocError> {
+    numbers.iter().try_fold(0u128, |set, n| match n {
+        0..=127 => Ok(set | 1 << n),
+        _ => Err(AocError::NumberTooLarge(id, *n)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_card() -> miette::Result<()> {
+        let card = parse_card("Card  3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1")?;
+        assert_eq!(3, card.id);
+        assert_eq!(2, card.matches());
+        assert_eq!(2, card.points());
+
+        assert!(parse_card("Card 3:  1 21 53 59 44 | 69 128").is_err());
+        assert!(parse_card("Card 3:  1 21 53 59 44 | 69 x").is_err());
+        assert!(parse_card("Card 3:  1 21 53 59 44").is_err());
+        Ok(())
+    }
+
+    /// Points of 33 or more matches, past `u32::MAX`, up to all 128 numbers matching
+    #[test]
+    fn test_many_matches() -> miette::Result<()> {
+        let numbers = (1..=40)
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let card = parse_card(&format!("Card 1: {numbers} | {numbers}"))?;
+        assert_eq!(40, card.matches());
+        assert_eq!(1 << 39, card.points());
+
+        let numbers = (0..=127)
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let card = parse_card(&format!("Card 1: {numbers} | {numbers}"))?;
+        assert_eq!(1 << 127, card.points());
+        Ok(())
+    }
+}
@@ -6,4 +6,22 @@ pub enum AocError {
     #[error(transparent)]
     #[diagnostic(code(aoc::io_error))]
     IoError(#[from] std::io::Error),
+    #[error("Card had bad format: '{0}'")]
+    #[diagnostic(code(aoc::bad_card))]
+    BadCard(String),
+    #[error("Card {0} holds number {1}, only 0 to 127 fit the bitset")]
+    #[diagnostic(code(aoc::number_too_large))]
+    NumberTooLarge(u32, u32),
+    #[error("Expected card {0} next, found card {1}")]
+    #[diagnostic(
+        code(aoc::non_contiguous_card),
+        help("card ids must run 1, 2, 3, ... without gaps")
+    )]
+    NonContiguousCard(u32, u32),
+    #[error("Points of all cards don't fit 128 bits")]
+    #[diagnostic(code(aoc::points_overflow))]
+    PointsOverflow,
+    #[error("Copies of all cards don't fit 128 bits")]
+    #[diagnostic(code(aoc::copies_overflow))]
+    CopiesOverflow,
 }
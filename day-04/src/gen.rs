@@ -0,0 +1,77 @@
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+/// A random input with the answers it was built to have
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    pub input: String,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+/// `size` cards of 10 winning numbers and 25 numbers you have, all from 1 to 99. Cards match
+/// less than one number on average, so copies don't grow exponentially with `size`.
+pub fn generate(seed: u64, size: usize) -> Puzzle {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut input = String::new();
+    let mut all_matches = vec![];
+
+    for id in 1..=size {
+        let matches = match rng.gen_range(0..100) {
+            0..=63 => 0,
+            n => 1 + (n - 64) / 9, // 1 to 4
+        };
+        let mut numbers = (1..=99u32).collect::<Vec<_>>();
+        numbers.shuffle(&mut rng);
+        let (winning, others) = numbers.split_at(10);
+        let mut have = [&winning[..matches], &others[..25 - matches]].concat();
+        have.shuffle(&mut rng);
+
+        let list = |numbers: &[u32]| {
+            numbers
+                .iter()
+                .map(|n| format!("{n:>2}"))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        input.push_str(&format!(
+            "Card {id:>3}: {} | {}\n",
+            list(winning),
+            list(&have)
+        ));
+        all_matches.push(matches);
+    }
+
+    let part1: u64 = all_matches
+        .iter()
+        .filter(|&&m| m > 0)
+        .map(|m| 1 << (m - 1))
+        .sum();
+    let mut copies = vec![1u64; size];
+    for (no, &m) in all_matches.iter().enumerate() {
+        for next in no + 1..(no + 1 + m).min(size) {
+            copies[next] += copies[no];
+        }
+    }
+    let part2: u64 = copies.iter().sum();
+
+    Puzzle {
+        input,
+        part1: Some(part1.to_string()),
+        part2: Some(part2.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate() -> miette::Result<()> {
+        for seed in 0..20 {
+            let puzzle = generate(seed, 100);
+            assert_eq!(puzzle.part1.unwrap(), crate::part1::process(&puzzle.input)?);
+            assert_eq!(puzzle.part2.unwrap(), crate::part2::process(&puzzle.input)?);
+        }
+        Ok(())
+    }
+}
@@ -1,85 +1,48 @@
-use std::collections::BTreeMap;
+use tracing::{debug, info_span};
 
-use nom::{
-    self,
-    bytes::complete::tag,
-    character::complete::{self, space1},
-    multi::separated_list1,
-    sequence::tuple,
-    IResult,
-};
-use tracing::debug;
+use crate::{card::parse_cards, custom_error::AocError};
 
-use crate::custom_error::AocError;
-
-// #[tracing::instrument]
+#[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String, AocError> {
-    // It's BTreeMap time as well: trying to keep track of card_ids and their copies
-    let mut scratchcards: BTreeMap<u32, u32> = BTreeMap::new();
-
-    let points = input
-        .lines()
-        .map(move |i| {
-            let (_, (card_id, winning_numbers, numbers_you_have)) = parse_card(i).unwrap();
-            if let Some(copies) = scratchcards.insert(card_id.clone(), 1) {
-                scratchcards.insert(card_id.clone(), copies + 1);
-            }
-
-            // Count numbers winning something on card's rhs
-            let count = numbers_you_have
-                .iter()
-                .filter(|number| winning_numbers.contains(number))
-                .map(|_| 1)
-                .sum::<u32>();
-
-            // Retrieve number of copies for the current card
-            let repetitions = scratchcards.get(&card_id);
-            if repetitions.is_none() {
-                panic!("Card {card_id:3} should have been registered by now...");
-            }
-            let repetitions = repetitions.unwrap().clone();
-
-            if count < 1 {
-                debug!("Card {card_id:3} occurs {repetitions:7} times");
-            } else {
-                for id in card_id + 1..card_id + 1 + count {
-                    if let Some(copies) = scratchcards.get(&id) {
-                        scratchcards.insert(id, repetitions + copies);
-                    } else {
-                        scratchcards.insert(id, repetitions);
-                    }
-                }
-                debug!("Card {card_id:3} occurs {repetitions:7} times");
-            }
-            repetitions // <- This is what counts in part2
-        })
-        .sum::<u32>();
-
-    Ok(points.to_string())
-}
+    let cards = info_span!("parse").in_scope(|| parse_cards(input))?;
+    let _solve = info_span!("solve").entered();
+
+    // Copies flow from a card to the `matches` cards following it. Rather than adding to each of
+    // them, note where a flow starts and ends and keep a running sum (prefix sum of deltas).
+    // Copies can double with every card, so sums are wide and checked.
+    let mut deltas: Vec<i128> = vec![0; cards.len() + 1];
+    let mut flowing = 0i128;
+    let mut total = 0i128;
+    let add = |a: i128, b: i128| a.checked_add(b).ok_or(AocError::CopiesOverflow);
+
+    for (no, card) in cards.iter().enumerate() {
+        let expected = no as u32 + 1;
+        if card.id != expected {
+            return Err(AocError::NonContiguousCard(expected, card.id));
+        }
+
+        flowing = add(flowing, deltas[no])?;
+        let repetitions = add(1, flowing)?; // The original plus all copies won so far
+        debug!("Card {:3} occurs {repetitions:7} times", card.id);
 
-/// Result rhs now holds: (card_id, winning_numbers, numbers_you_have)
-fn parse_card(i: &str) -> IResult<&str, (u32, Vec<u32>, Vec<u32>)> {
-    // Now we need the header's card number
-    let (i, (_, _, card_id, _)) = tuple((tag("Card"), space1, complete::u32, tag(":")))(i)?;
-
-    // Then get what's required to eval cards.
-    let (i, (_, winning_numbers, _, _, numbers_you_have)) = tuple((
-        space1, // *Must* eat space before e.g. '30  1 29'...
-        separated_list1(space1, complete::u32),
-        tag(" |"),
-        space1,
-        separated_list1(space1, complete::u32),
-    ))(i)?;
-
-    if i != "" {
-        panic!("Parsing problem: Card had unrecognized tail '{i}'");
+        // Cards never make you copy a card past the end of the table.
+        let first = (no + 1).min(cards.len());
+        let end = (no + 1 + card.matches() as usize).min(cards.len());
+        deltas[first] = add(deltas[first], repetitions)?;
+        deltas[end] = add(deltas[end], -repetitions)?;
+
+        total = add(total, repetitions)?; // <- This is what counts in part2
     }
-    Ok((i, (card_id, winning_numbers, numbers_you_have)))
+
+    Ok(total.to_string())
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::{BTreeSet, VecDeque};
+
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
@@ -93,4 +56,61 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
         assert_eq!("30", process(input)?);
         Ok(())
     }
+
+    #[test]
+    fn test_non_contiguous() {
+        let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1";
+        assert!(process(input).is_err());
+    }
+
+    /// Every card winning copies of the next ten doubles the copies with each card.
+    #[test]
+    fn test_copies_overflow() {
+        let input = (1..=200)
+            .map(|id| format!("Card {id}: 1 2 3 4 5 6 7 8 9 10 | 1 2 3 4 5 6 7 8 9 10"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(matches!(process(&input), Err(AocError::CopiesOverflow)));
+    }
+
+    /// Scratch every card, the original and each copy won, one at a time.
+    fn scratch_all(cards: &[(BTreeSet<u32>, BTreeSet<u32>)]) -> u64 {
+        let mut pile = (0..cards.len()).collect::<VecDeque<_>>();
+        let mut scratched = 0;
+        while let Some(no) = pile.pop_front() {
+            scratched += 1;
+            let (winning, have) = &cards[no];
+            let matches = have.iter().filter(|n| winning.contains(n)).count();
+            pile.extend((no + 1..=no + matches).filter(|&next| next < cards.len()));
+        }
+        scratched
+    }
+
+    proptest! {
+        #[test]
+        fn process_counts_every_copy(
+            cards in prop::collection::vec(
+                (
+                    prop::collection::btree_set(1..30u32, 1..6),
+                    prop::collection::btree_set(1..30u32, 1..10),
+                ),
+                1..12,
+            ),
+        ) {
+            let list = |numbers: &BTreeSet<u32>| {
+                numbers.iter().map(|n| format!("{n:>2}")).collect::<Vec<_>>().join(" ")
+            };
+            let input = cards
+                .iter()
+                .enumerate()
+                .map(|(no, (winning, have))| {
+                    format!("Card {}: {} | {}", no + 1, list(winning), list(have))
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            prop_assert_eq!(scratch_all(&cards).to_string(), process(&input).unwrap());
+        }
+    }
 }
@@ -0,0 +1,535 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    ops::Range,
+};
+
+use nom::{
+    self,
+    bytes::complete::tag,
+    character::complete::{self, alpha1, line_ending, multispace0, space1},
+    combinator::all_consuming,
+    multi::{many1, separated_list1},
+    sequence::{delimited, preceded, separated_pair, terminated, tuple},
+    IResult,
+};
+
+use crate::custom_error::AocError;
+
+/// One `X-to-Y map:` section of the almanac, a piecewise map with sorted and disjoint source
+/// ranges. Values outside all source ranges map to themselves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Router<'a> {
+    pub from: &'a str,
+    pub to: &'a str,
+    // src_range, dst_start
+    routes: Vec<(Range<u64>, u64)>,
+}
+
+impl<'a> Router<'a> {
+    /// Build a router from the almanac's `(dst_start, src_start, length)` lines.
+    pub fn new(from: &'a str, to: &'a str, lines: &[(u64, u64, u64)]) -> Result<Self, AocError> {
+        let bad_line = |(dst, src, len): &(u64, u64, u64)| {
+            AocError::BadAlmanac(format!(
+                "{from}-to-{to} map has bad line '{dst} {src} {len}'"
+            ))
+        };
+
+        let mut routes = vec![];
+        for line @ (dst, src, len) in lines {
+            // Both ranges must fit into u64 space.
+            match (src.checked_add(*len), dst.checked_add(*len)) {
+                (Some(end), Some(_)) if *len > 0 => routes.push((*src..end, *dst)),
+                (Some(_), Some(_)) => {} // Nothing to route
+                _ => return Err(bad_line(line)),
+            }
+        }
+        routes.sort_by_key(|(range, _)| range.start);
+        if routes.windows(2).any(|w| w[0].0.end > w[1].0.start) {
+            return Err(AocError::OverlappingRoutes(
+                from.to_string(),
+                to.to_string(),
+            ));
+        }
+
+        Ok(Self { from, to, routes })
+    }
+
+    /// A router from `category` to itself
+    pub fn identity(category: &'a str) -> Self {
+        Self {
+            from: category,
+            to: category,
+            routes: vec![],
+        }
+    }
+
+    pub fn routes(&self) -> &[(Range<u64>, u64)] {
+        &self.routes
+    }
+
+    pub fn route(&self, src: u64) -> u64 {
+        let at = self.routes.partition_point(|(range, _)| range.end <= src);
+        match self.routes.get(at) {
+            Some((src_range, dst_start)) if src_range.contains(&src) => {
+                let dst_offset = src - src_range.start;
+
+                dst_start + dst_offset
+            }
+            _ => src,
+        }
+    }
+
+    /// Route a whole range of values, returns the destination ranges in order of their sources.
+    pub fn route_range(&self, range: Range<u64>) -> impl Iterator<Item = Range<u64>> {
+        self.pieces(range)
+            .into_iter()
+            .map(|(src, dst)| dst..dst + (src.end - src.start))
+    }
+
+    /// Compose this router and `next` into one router doing both steps at once.
+    pub fn then(&self, next: &Router<'a>) -> Router<'a> {
+        let mut routes: Vec<(Range<u64>, u64)> = vec![];
+
+        // Route every piece of the whole domain through self, then split its image by next.
+        for (src, dst) in self.pieces(0..u64::MAX) {
+            let image = dst..dst + (src.end - src.start);
+            for (mid, dst_next) in next.pieces(image) {
+                let start = src.start + (mid.start - dst);
+                let end = start + (mid.end - mid.start);
+                if start == dst_next {
+                    continue; // Identity, no route needed
+                }
+                match routes.last_mut() {
+                    // Extend the last route where source and destination both continue it.
+                    Some((last, last_dst))
+                        if last.end == start && *last_dst + (last.end - last.start) == dst_next =>
+                    {
+                        last.end = end
+                    }
+                    _ => routes.push((start..end, dst_next)),
+                }
+            }
+        }
+
+        Router {
+            from: self.from,
+            to: next.to,
+            routes,
+        }
+    }
+
+    /// All values routed to `dst`: the sources of routes leading there, plus `dst` itself unless
+    /// a route claims it as a source. There may be none, one or several.
+    pub fn preimages(&self, dst: u64) -> Vec<u64> {
+        let mut preimages = self
+            .routes
+            .iter()
+            .filter(|(src, dst_start)| {
+                (*dst_start..dst_start + (src.end - src.start)).contains(&dst)
+            })
+            .map(|(src, dst_start)| src.start + (dst - dst_start))
+            .collect::<Vec<_>>();
+        if !self.routes.iter().any(|(src, _)| src.contains(&dst)) {
+            preimages.push(dst);
+        }
+        preimages.sort();
+        preimages
+    }
+
+    /// All ranges of values routed into `range`, in order of their sources
+    pub fn preimage_range(&self, range: Range<u64>) -> Vec<Range<u64>> {
+        self.pieces(0..u64::MAX)
+            .into_iter()
+            .filter_map(|(src, dst)| {
+                let lo = range.start.max(dst);
+                let hi = range.end.min(dst + (src.end - src.start));
+                (lo < hi).then(|| src.start + (lo - dst)..src.start + (hi - dst))
+            })
+            .collect()
+    }
+
+    /// The lowest value any of `sources` is routed to, found backwards: walk destinations in
+    /// increasing order and stop at the first whose preimage meets one of `sources`.
+    pub fn lowest_image(&self, sources: &[Range<u64>]) -> Option<u64> {
+        let mut pieces = self.pieces(0..u64::MAX);
+        pieces.sort_by_key(|(_, dst)| *dst);
+
+        let mut lowest: Option<u64> = None;
+        for (src, dst) in pieces {
+            if lowest.is_some_and(|lowest| lowest <= dst) {
+                break; // Nothing lower to come
+            }
+            for source in sources {
+                let lo = source.start.max(src.start);
+                if lo < source.end.min(src.end) {
+                    let image = dst + (lo - src.start);
+                    lowest = Some(lowest.map_or(image, |lowest| lowest.min(image)));
+                }
+            }
+        }
+        lowest
+    }
+
+    /// Split `range` into pieces of `(src_range, dst_start)`, each routed by one route or not
+    /// routed at all (then `dst_start == src_range.start`).
+    fn pieces(&self, range: Range<u64>) -> Vec<(Range<u64>, u64)> {
+        let mut pieces = vec![];
+        let mut at = range.start;
+        let mut i = self.routes.partition_point(|(src, _)| src.end <= at);
+
+        while at < range.end {
+            let end = match self.routes.get(i) {
+                Some((src, dst)) if src.start <= at => {
+                    let end = src.end.min(range.end);
+                    pieces.push((at..end, dst + (at - src.start)));
+                    i += 1;
+                    end
+                }
+                Some((src, _)) => {
+                    let end = src.start.min(range.end);
+                    pieces.push((at..end, at));
+                    end
+                }
+                None => {
+                    pieces.push((at..range.end, at));
+                    range.end
+                }
+            };
+            at = end;
+        }
+        pieces
+    }
+}
+
+/// The seeds and all maps, categories are whatever the section headers name.
+#[derive(Debug)]
+pub struct Almanac<'a> {
+    pub seeds: Vec<u64>,
+    pub routers: Vec<Router<'a>>,
+}
+
+impl<'a> Almanac<'a> {
+    pub fn parse(input: &'a str) -> Result<Self, AocError> {
+        let (_, (seeds, sections)) =
+            all_consuming(tuple((seeds, terminated(many1(section), multispace0))))(input)
+                .map_err(|e| AocError::BadAlmanac(e.to_string()))?;
+
+        let routers = sections
+            .iter()
+            .map(|((from, to), lines)| Router::new(from, to, lines))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { seeds, routers })
+    }
+
+    /// The seeds read as pairs of (start, length), as part 2 has them
+    pub fn seed_ranges(&self) -> Result<Vec<Range<u64>>, AocError> {
+        self.seeds
+            .chunks(2)
+            .map(|pair| match *pair {
+                [start, len] => match start.checked_add(len) {
+                    Some(end) => Ok(start..end),
+                    None => Err(AocError::BadSeedRange(start, Some(len))),
+                },
+                [start] => Err(AocError::BadSeedRange(start, None)),
+                _ => unreachable!("chunks of 2"),
+            })
+            .collect()
+    }
+
+    /// The routers to pass in turn to get from category `from` to category `to`, found by a
+    /// breadth first search over the section headers.
+    pub fn path(&self, from: &str, to: &str) -> Result<Vec<&Router<'a>>, AocError> {
+        // Remember by which router we reached a category first.
+        let mut reached_by: BTreeMap<&str, Option<&Router>> = BTreeMap::from([(from, None)]);
+        let mut queue = VecDeque::from([from]);
+
+        while let Some(category) = queue.pop_front() {
+            if category == to {
+                break;
+            }
+            for router in self.routers.iter().filter(|r| r.from == category) {
+                if !reached_by.contains_key(router.to) {
+                    reached_by.insert(router.to, Some(router));
+                    queue.push_back(router.to);
+                }
+            }
+        }
+
+        // Walk back from the target.
+        let mut path = vec![];
+        let mut category = to;
+        while category != from {
+            match reached_by.get(category) {
+                Some(Some(router)) => {
+                    path.push(*router);
+                    category = router.from;
+                }
+                _ => return Err(AocError::NoRoute(from.to_string(), to.to_string())),
+            }
+        }
+        path.reverse();
+        Ok(path)
+    }
+
+    /// Route a single value from category `from` to category `to`
+    pub fn route(&self, from: &str, to: &str, value: u64) -> Result<u64, AocError> {
+        Ok(self
+            .path(from, to)?
+            .iter()
+            .fold(value, |value, router| router.route(value)))
+    }
+
+    /// Every category on the way from `from` to `to`, with the values `value` takes there. If
+    /// the almanac only leads from `to` to `from`, walk back through preimages instead, where a
+    /// value may come from several values (or none).
+    pub fn trace<'s>(
+        &'s self,
+        from: &'s str,
+        to: &str,
+        value: u64,
+    ) -> Result<Vec<(&'s str, Vec<u64>)>, AocError> {
+        let mut trace = vec![(from, vec![value])];
+
+        if let Ok(path) = self.path(from, to) {
+            for router in path {
+                let (_, values) = &trace[trace.len() - 1];
+                let values = values.iter().map(|v| router.route(*v)).collect();
+                trace.push((router.to, values));
+            }
+        } else {
+            for router in self.path(to, from)?.into_iter().rev() {
+                let (_, values) = &trace[trace.len() - 1];
+                let values = values.iter().flat_map(|v| router.preimages(*v)).collect();
+                trace.push((router.from, values));
+            }
+        }
+        Ok(trace)
+    }
+
+    /// Collapse all routers on the way from category `from` to category `to` into one.
+    pub fn flatten(&self, from: &'a str, to: &str) -> Result<Router<'a>, AocError> {
+        Ok(self
+            .path(from, to)?
+            .iter()
+            .fold(Router::identity(from), |flat, router| flat.then(router)))
+    }
+}
+
+fn seeds(i: &str) -> IResult<&str, Vec<u64>> {
+    preceded(tag("seeds: "), separated_list1(space1, complete::u64))(i)
+}
+
+/// A section's header as (from, to) and its lines as (dst_start, src_start, length)
+type Section<'a> = ((&'a str, &'a str), Vec<(u64, u64, u64)>);
+
+fn section(i: &str) -> IResult<&str, Section<'_>> {
+    tuple((
+        delimited(
+            multispace0,
+            separated_pair(alpha1, tag("-to-"), alpha1),
+            tuple((tag(" map:"), line_ending)),
+        ),
+        many1(terminated(
+            tuple((
+                complete::u64,
+                preceded(tag(" "), complete::u64),
+                preceded(tag(" "), complete::u64),
+            )),
+            multispace0,
+        )),
+    ))(i)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_path() -> miette::Result<()> {
+        // Sections in any order, with a side branch
+        let input = "seeds: 79 14
+
+soil-to-water map:
+0 50 10
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+";
+        let almanac = Almanac::parse(input)?;
+        let path = almanac.path("seed", "water")?;
+        assert_eq!(
+            vec![("seed", "soil"), ("soil", "water")],
+            path.iter().map(|r| (r.from, r.to)).collect::<Vec<_>>()
+        );
+        assert_eq!(81, almanac.route("seed", "soil", 79)?);
+        assert_eq!(2, almanac.route("seed", "water", 50)?);
+        assert_eq!(14, almanac.route("seed", "seed", 14)?);
+        assert!(almanac.route("water", "seed", 1).is_err());
+        assert!(almanac.route("seed", "location", 1).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_flatten() -> miette::Result<()> {
+        let input = "seeds: 0
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+";
+        let almanac = Almanac::parse(input)?;
+        let flat = almanac.flatten("seed", "fertilizer")?;
+        assert_eq!(("seed", "fertilizer"), (flat.from, flat.to));
+        for seed in 0..120 {
+            assert_eq!(almanac.route("seed", "fertilizer", seed)?, flat.route(seed));
+        }
+        assert_eq!(
+            vec![39..40, 0..2, 37..38],
+            flat.route_range(0..1)
+                .chain(flat.route_range(15..17))
+                .chain(flat.route_range(50..51))
+                .collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_inverse() -> miette::Result<()> {
+        let input = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+";
+        let almanac = Almanac::parse(input)?;
+        let soil = &almanac.routers[0];
+
+        // 50 routes elsewhere, so only 98 reaches it. Below 50 values route to themselves.
+        assert_eq!(vec![96], soil.preimages(98));
+        assert_eq!(vec![98], soil.preimages(50));
+        assert_eq!(vec![48], soil.preimages(48));
+        assert_eq!(vec![48..50, 50..51, 98..100], soil.preimage_range(48..53));
+
+        let trace = almanac.trace("fertilizer", "seed", 39)?;
+        assert_eq!(
+            vec![
+                ("fertilizer", vec![39]),
+                ("soil", vec![0]),
+                ("seed", vec![0])
+            ],
+            trace
+        );
+        assert_eq!(
+            vec![
+                ("seed", vec![79]),
+                ("soil", vec![81]),
+                ("fertilizer", vec![81])
+            ],
+            almanac.trace("seed", "fertilizer", 79)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_lowest_image() -> miette::Result<()> {
+        let almanac = Almanac::parse(include_str!("../input1.txt"))?;
+        let router = almanac.flatten("seed", "location")?;
+        let seeds = almanac
+            .seeds
+            .chunks_exact(2)
+            .map(|pair| pair[0]..pair[0] + pair[1])
+            .collect::<Vec<_>>();
+
+        let forward = seeds
+            .iter()
+            .flat_map(|range| router.route_range(range.clone()))
+            .map(|locations| locations.start)
+            .min();
+        assert_eq!(forward, router.lowest_image(&seeds));
+        Ok(())
+    }
+
+    #[test]
+    fn test_bad_almanac() {
+        assert!(Almanac::parse("seeds: 79 14\n\nseed-to-soil map:\n50 98\n").is_err());
+        assert!(Almanac::parse("seeds: 79 14\n\nseed-to-soil:\n50 98 2\n").is_err());
+        assert!(Almanac::parse("seeds: 79\n\nseed-to-soil map:\n50 98 2\n0 99 5\n").is_err());
+    }
+
+    /// A seed range running past `u64::MAX` has no end, a lone start has no length
+    #[test]
+    fn test_bad_seed_ranges() -> miette::Result<()> {
+        let almanac =
+            Almanac::parse("seeds: 18446744073709551615 5\n\nseed-to-soil map:\n50 98 2\n")?;
+        assert!(almanac.seed_ranges().is_err());
+        let almanac = Almanac::parse("seeds: 79 14 55\n\nseed-to-soil map:\n50 98 2\n")?;
+        assert!(almanac.seed_ranges().is_err());
+        let almanac = Almanac::parse("seeds: 79 14 55 13\n\nseed-to-soil map:\n50 98 2\n")?;
+        assert_eq!(vec![79..93, 55..68], almanac.seed_ranges()?);
+        Ok(())
+    }
+
+    /// Routers with disjoint source ranges, built from (gap, length, dst_start) triples
+    fn router_strategy() -> impl Strategy<Value = Router<'static>> {
+        prop::collection::vec((0..20u64, 1..20u64, 0..300u64), 0..6).prop_map(|triples| {
+            let mut start = 0;
+            let lines = triples
+                .into_iter()
+                .map(|(gap, len, dst)| {
+                    start += gap;
+                    let line = (dst, start, len);
+                    start += len;
+                    line
+                })
+                .collect::<Vec<_>>();
+            Router::new("a", "b", &lines).unwrap()
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn composition_routes_like_steps(
+            routers in prop::collection::vec(router_strategy(), 1..5),
+            value in 0..400u64,
+        ) {
+            let flat = routers.iter().fold(Router::identity("a"), |flat, r| flat.then(r));
+            let stepwise = routers.iter().fold(value, |value, r| r.route(value));
+            prop_assert_eq!(stepwise, flat.route(value));
+        }
+
+        #[test]
+        fn preimages_route_back(router in router_strategy(), value in 0..400u64) {
+            for preimage in router.preimages(value) {
+                prop_assert_eq!(value, router.route(preimage));
+            }
+            let brute_force = (0..800u64).filter(|v| router.route(*v) == value).collect::<Vec<_>>();
+            prop_assert_eq!(brute_force, router.preimages(value));
+        }
+
+        #[test]
+        fn range_routes_like_points(router in router_strategy(), start in 0..300u64, len in 0..100u64) {
+            let mut ranged = router.route_range(start..start + len).flatten().collect::<Vec<_>>();
+            let mut pointwise = (start..start + len).map(|v| router.route(v)).collect::<Vec<_>>();
+            ranged.sort();
+            pointwise.sort();
+            prop_assert_eq!(pointwise, ranged);
+        }
+    }
+}
@@ -0,0 +1,30 @@
+//! Print the full category path of a value, e.g.
+//!
+//!     cargo run -p day-05 --bin trace -- seed 79
+//!     cargo run -p day-05 --bin trace -- location 46 seed
+//!
+//! Values go from their category to `location` (or `seed` for locations), or to the category
+//! given last. Going backwards, a value may come from several values.
+use day_05::almanac::Almanac;
+use itertools::Itertools;
+use miette::{miette, IntoDiagnostic};
+
+fn main() -> miette::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let mut args = std::env::args().skip(1);
+    let usage = || miette!("Usage: trace <category> <value> [<category>]");
+
+    let from = args.next().ok_or_else(usage)?;
+    let value = args.next().ok_or_else(usage)?.parse().into_diagnostic()?;
+    let to = args.next().unwrap_or_else(|| match from.as_str() {
+        "location" => "seed".to_string(),
+        _ => "location".to_string(),
+    });
+
+    let almanac = Almanac::parse(include_str!("../../input1.txt"))?;
+    for (category, values) in almanac.trace(&from, &to, value)? {
+        println!("{category:>12} {}", values.iter().join(", "));
+    }
+    Ok(())
+}
@@ -6,4 +6,22 @@ pub enum AocError {
     #[error(transparent)]
     #[diagnostic(code(aoc::io_error))]
     IoError(#[from] std::io::Error),
+    #[error("Almanac had bad format: {0}")]
+    #[diagnostic(code(aoc::bad_almanac))]
+    BadAlmanac(String),
+    #[error("Almanac has no way from {0} to {1}")]
+    #[diagnostic(code(aoc::no_route))]
+    NoRoute(String, String),
+    #[error("The {0}-to-{1} map has overlapping source ranges")]
+    #[diagnostic(code(aoc::overlapping_routes))]
+    OverlappingRoutes(String, String),
+    #[error("Seed range starting at {0} has length {1:?}, missing or running past 64 bits")]
+    #[diagnostic(
+        code(aoc::bad_seed_range),
+        help("part 2 reads seeds as pairs of start and length")
+    )]
+    BadSeedRange(u64, Option<u64>),
+    #[error("Almanac lists no seeds")]
+    #[diagnostic(code(aoc::no_seeds))]
+    NoSeeds,
 }
@@ -0,0 +1,116 @@
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+/// A random input with the answers it was built to have
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    pub input: String,
+    pub part1: Option<String>,
+    /// Known when there are few enough seeds to route one by one
+    pub part2: Option<String>,
+}
+
+const CATEGORIES: [&str; 8] = [
+    "seed",
+    "soil",
+    "fertilizer",
+    "water",
+    "light",
+    "temperature",
+    "humidity",
+    "location",
+];
+
+/// Most seeds to route one at a time for part 2's answer
+const MAX_SEEDS: u64 = 5_000_000;
+
+/// `size` seed ranges and `size` lines in each of the seven maps, values below 2^32.
+pub fn generate(seed: u64, size: usize) -> Puzzle {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let size = size.max(1);
+    let limit = 1u64 << 32;
+
+    let seeds = (0..size)
+        .map(|_| {
+            let len = rng.gen_range(1..=10_000);
+            (rng.gen_range(0..limit - len), len)
+        })
+        .collect::<Vec<_>>();
+    let mut input = "seeds:".to_string();
+    for (start, len) in &seeds {
+        input.push_str(&format!(" {start} {len}"));
+    }
+    input.push('\n');
+
+    // Disjoint sources between sorted cut points, some of the gaps between them unmapped.
+    let mut maps = vec![];
+    for names in CATEGORIES.windows(2) {
+        let mut cuts = (0..2 * size)
+            .map(|_| rng.gen_range(0..limit))
+            .collect::<Vec<_>>();
+        cuts.sort();
+        cuts.dedup();
+        let mut lines = cuts
+            .chunks_exact(2)
+            .map(|pair| {
+                let (src, len) = (pair[0], pair[1] - pair[0] + 1);
+                (rng.gen_range(0..=limit - len), src, len)
+            })
+            .collect::<Vec<_>>();
+        lines.shuffle(&mut rng);
+
+        input.push_str(&format!("\n{}-to-{} map:\n", names[0], names[1]));
+        for (dst, src, len) in &lines {
+            input.push_str(&format!("{dst} {src} {len}\n"));
+        }
+        lines.sort_by_key(|(_, src, _)| *src);
+        maps.push(lines);
+    }
+
+    let locate = |mut value: u64| {
+        for lines in &maps {
+            let i = lines.partition_point(|(_, src, _)| *src <= value);
+            if let Some((dst, src, len)) = i.checked_sub(1).map(|i| lines[i]) {
+                if value < src + len {
+                    value = dst + value - src;
+                }
+            }
+        }
+        value
+    };
+    let part1 = seeds
+        .iter()
+        .flat_map(|(start, len)| [*start, *len])
+        .map(locate)
+        .min();
+    let part2 = (seeds.iter().map(|(_, len)| len).sum::<u64>() <= MAX_SEEDS)
+        .then(|| {
+            seeds
+                .iter()
+                .flat_map(|(start, len)| *start..start + len)
+                .map(locate)
+                .min()
+        })
+        .flatten();
+
+    Puzzle {
+        input,
+        part1: part1.map(|location| location.to_string()),
+        part2: part2.map(|location| location.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate() -> miette::Result<()> {
+        for seed in 0..10 {
+            let puzzle = generate(seed, 10);
+            assert_eq!(puzzle.part1.unwrap(), crate::part1::process(&puzzle.input)?);
+            assert_eq!(puzzle.part2.unwrap(), crate::part2::process(&puzzle.input)?);
+        }
+        assert_eq!(None, generate(0, 1000).part2);
+        Ok(())
+    }
+}
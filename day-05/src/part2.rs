@@ -1,19 +1,154 @@
-use crate::custom_error::AocError;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
-#[tracing::instrument]
-pub fn process(_input: &str) -> miette::Result<String, AocError> {
-    todo!("day part 2");
+use tracing::{debug, info_span};
+
+use crate::{almanac::Almanac, custom_error::AocError};
+
+#[tracing::instrument(skip(input))]
+pub fn process(input: &str) -> miette::Result<String, AocError> {
+    let almanac = info_span!("parse").in_scope(|| Almanac::parse(input))?;
+    let _solve = info_span!("solve").entered();
+
+    // Seeds now come in pairs of (start, length), far too many to route one by one. Instead
+    // route whole ranges through a single router doing all steps at once.
+    let seeds = almanac.seed_ranges()?;
+    let router = almanac.flatten("seed", "location")?;
+    debug!(routes = router.routes().len(), "flattened seed to location");
+
+    #[cfg(not(feature = "parallel"))]
+    let location = seeds
+        .into_iter()
+        .flat_map(|seeds| router.route_range(seeds))
+        .map(|locations| locations.start)
+        .min();
+    // Seed ranges route independently of each other.
+    #[cfg(feature = "parallel")]
+    let location = seeds
+        .into_par_iter()
+        .flat_map_iter(|seeds| router.route_range(seeds))
+        .map(|locations| locations.start)
+        .min();
+
+    Ok(location.ok_or(AocError::NoSeeds)?.to_string())
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
     fn test_process() -> miette::Result<()> {
-        todo!("haven't built test yet");
-        let input = "";
-        assert_eq!("", process(input)?);
+        let input = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
+        assert_eq!("46", process(input)?);
         Ok(())
     }
+
+    #[test]
+    fn test_bad_seed_ranges() {
+        assert!(process("seeds: 18446744073709551615 5\n\nseed-to-soil map:\n50 98 2\n").is_err());
+    }
+
+    const CATEGORIES: [&str; 8] = [
+        "seed",
+        "soil",
+        "fertilizer",
+        "water",
+        "light",
+        "temperature",
+        "humidity",
+        "location",
+    ];
+
+    /// A map's one to four `(dst_start, src_start, length)` lines with disjoint sources, from
+    /// (gap, length, dst_start) triples
+    fn lines_strategy() -> impl Strategy<Value = Vec<(u64, u64, u64)>> {
+        prop::collection::vec((0..50u64, 1..50u64, 0..500u64), 1..5).prop_map(|triples| {
+            let mut start = 0;
+            triples
+                .into_iter()
+                .map(|(gap, len, dst)| {
+                    start += gap;
+                    let line = (dst, start, len);
+                    start += len;
+                    line
+                })
+                .collect()
+        })
+    }
+
+    /// Every seed on its own through every map in turn, the first line covering it wins.
+    fn lowest_location(seeds: &[(u64, u64)], maps: &[Vec<(u64, u64, u64)>]) -> Option<u64> {
+        seeds
+            .iter()
+            .flat_map(|&(start, len)| start..start + len)
+            .map(|seed| {
+                maps.iter().fold(seed, |value, lines| {
+                    match lines
+                        .iter()
+                        .find(|(_, src, len)| (*src..src + len).contains(&value))
+                    {
+                        Some((dst, src, _)) => dst + value - src,
+                        None => value,
+                    }
+                })
+            })
+            .min()
+    }
+
+    proptest! {
+        #[test]
+        fn process_finds_the_lowest_location(
+            seeds in prop::collection::vec((0..400u64, 1..60u64), 1..4),
+            maps in prop::collection::vec(lines_strategy(), 7),
+        ) {
+            let mut input = "seeds:".to_string();
+            for (start, len) in &seeds {
+                input += &format!(" {start} {len}");
+            }
+            for (names, lines) in CATEGORIES.windows(2).zip(&maps) {
+                input += &format!("\n\n{}-to-{} map:", names[0], names[1]);
+                for (dst, src, len) in lines {
+                    input += &format!("\n{dst} {src} {len}");
+                }
+            }
+
+            let expected = lowest_location(&seeds, &maps).unwrap().to_string();
+            prop_assert_eq!(expected, process(&input).unwrap());
+        }
+    }
 }
@@ -8,4 +8,7 @@ pub enum AocError {
     IoError(#[from] std::io::Error),
     #[error("Label invalid for Card")]
     BadLabelError(String),
+    #[error("Hand is not like `32T3K 765`: {0}")]
+    #[diagnostic(code(aoc::bad_hand))]
+    BadHand(String),
 }
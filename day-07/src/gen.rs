@@ -0,0 +1,108 @@
+use std::collections::HashSet;
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+/// A random input with the answers it was built to have
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    pub input: String,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+/// Card counts of every hand type, weakest first
+const TYPES: [&[usize]; 7] = [
+    &[1, 1, 1, 1, 1],
+    &[2, 1, 1, 1],
+    &[2, 2, 1],
+    &[3, 1, 1],
+    &[3, 2],
+    &[4, 1],
+    &[5],
+];
+
+const LABELS: &str = "23456789TJQKA";
+const LABELS_WITH_JOKERS: &str = "J23456789TQKA";
+
+/// Distinct hands there are to deal
+pub const MAX_SIZE: usize = LABELS.len().pow(5);
+
+/// `size` distinct hands (at most `MAX_SIZE`) with bids up to 1000, all hand types equally
+/// likely.
+pub fn generate(seed: u64, size: usize) -> Puzzle {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let size = size.min(MAX_SIZE);
+    let labels = LABELS.chars().collect::<Vec<_>>();
+
+    let mut seen = HashSet::new();
+    let mut hands = vec![];
+    while hands.len() < size {
+        let counts = TYPES[rng.gen_range(0..TYPES.len())];
+        let mut cards = vec![];
+        for (label, count) in labels.choose_multiple(&mut rng, counts.len()).zip(counts) {
+            cards.extend(std::iter::repeat_n(*label, *count));
+        }
+        cards.shuffle(&mut rng);
+        let cards = cards.into_iter().collect::<String>();
+        if seen.insert(cards.clone()) {
+            hands.push((cards, rng.gen_range(1..=1000u64)));
+        }
+    }
+
+    let input = hands
+        .iter()
+        .map(|(cards, bid)| format!("{cards} {bid}\n"))
+        .collect();
+    Puzzle {
+        input,
+        part1: Some(winnings(&hands, false).to_string()),
+        part2: Some(winnings(&hands, true).to_string()),
+    }
+}
+
+/// Total winnings, ranking hands by type and then card by card
+fn winnings(hands: &[(String, u64)], jokers: bool) -> u64 {
+    let order = if jokers { LABELS_WITH_JOKERS } else { LABELS };
+    let mut ranked = hands
+        .iter()
+        .map(|(cards, bid)| {
+            let mut counts = order
+                .chars()
+                .filter(|&label| !(jokers && label == 'J'))
+                .map(|label| cards.chars().filter(|&c| c == label).count())
+                .filter(|&count| count > 0)
+                .collect::<Vec<_>>();
+            counts.sort_by(|a, b| b.cmp(a));
+            // Jokers join the largest group, or make five of a kind on their own.
+            let wild = 5 - counts.iter().sum::<usize>();
+            match counts.first_mut() {
+                Some(largest) => *largest += wild,
+                None => counts.push(wild),
+            }
+            let kind = TYPES.iter().position(|t| *t == counts.as_slice());
+            let values = cards.chars().map(|c| order.find(c)).collect::<Vec<_>>();
+            ((kind, values), *bid)
+        })
+        .collect::<Vec<_>>();
+    ranked.sort();
+    ranked
+        .iter()
+        .enumerate()
+        .map(|(i, (_, bid))| (i as u64 + 1) * bid)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate() -> miette::Result<()> {
+        for seed in 0..20 {
+            let puzzle = generate(seed, 200);
+            assert_eq!(puzzle.part1.unwrap(), crate::part1::process(&puzzle.input)?);
+            assert_eq!(puzzle.part2.unwrap(), crate::part2::process(&puzzle.input)?);
+        }
+        Ok(())
+    }
+}
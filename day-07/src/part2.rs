@@ -8,10 +8,13 @@ use itertools::Itertools;
 use nom::{
     self,
     character::complete::{self, anychar, space1},
+    combinator::all_consuming,
     sequence::tuple,
     IResult,
 };
-use tracing::debug;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use tracing::{debug, info_span};
 
 use crate::custom_error::AocError;
 
@@ -174,29 +177,51 @@ impl Ord for Hand {
     }
 }
 
-// #[tracing::instrument]
+#[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String, AocError> {
-    let winnings: u32 = input
+    let parse = info_span!("parse").entered();
+    #[cfg(not(feature = "parallel"))]
+    let mut hands = input
         .lines()
-        .map(|line| parse_line(line))
-        .sorted()
+        .map(parse_line)
+        .collect::<Result<Vec<_>, _>>()?;
+    // Scoring a hand doesn't depend on the others.
+    #[cfg(feature = "parallel")]
+    let mut hands = input
+        .par_lines()
+        .map(parse_line)
+        .collect::<Result<Vec<_>, _>>()?;
+    drop(parse);
+
+    let _solve = info_span!("solve").entered();
+    hands.sort();
+    let winnings: u64 = hands
+        .iter()
         .enumerate()
         .map(|(i, hand)| {
             let rank = i + 1;
-            rank as u32 * hand.bid
+            rank as u64 * hand.bid as u64
         })
         .sum();
 
     Ok(winnings.to_string())
 }
 
-fn parse_line(line: &str) -> Hand {
-    let (_, hand) = hand(line).unwrap();
-    hand
+fn parse_line(line: &str) -> Result<Hand, AocError> {
+    let (_, (labels, bid)) = hand(line).map_err(|_| AocError::BadHand(line.to_string()))?;
+    let cards = [
+        Card::new(labels[0])?,
+        Card::new(labels[1])?,
+        Card::new(labels[2])?,
+        Card::new(labels[3])?,
+        Card::new(labels[4])?,
+    ];
+    Ok(Hand::new(&cards, bid))
 }
 
-fn hand(i: &str) -> IResult<&str, Hand> {
-    let (i, (l1, l2, l3, l4, l5, _, bid)) = tuple((
+/// Parse a line like `32T3K 765` into its labels and bid
+fn hand(i: &str) -> IResult<&str, ([char; 5], u32)> {
+    let (i, (l1, l2, l3, l4, l5, _, bid)) = all_consuming(tuple((
         anychar,
         anychar,
         anychar,
@@ -204,24 +229,16 @@ fn hand(i: &str) -> IResult<&str, Hand> {
         anychar,
         space1,
         complete::u32,
-    ))(i)?;
-    Ok((
-        i,
-        Hand::new(
-            &[
-                Card::new(l1).unwrap(),
-                Card::new(l2).unwrap(),
-                Card::new(l3).unwrap(),
-                Card::new(l4).unwrap(),
-                Card::new(l5).unwrap(),
-            ],
-            bid,
-        ),
-    ))
+    )))(i)?;
+    Ok((i, ([l1, l2, l3, l4, l5], bid)))
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
+
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
@@ -234,4 +251,73 @@ QQQJA 483";
         assert_eq!("5905", process(input)?);
         Ok(())
     }
+
+    /// Inputs the fuzzer found panicking
+    #[test]
+    fn test_bad_hands() {
+        assert!(process("3").is_err());
+        assert!(process("32T3X 1").is_err());
+        assert!(process("32T3K").is_err());
+        // Not a crash, but no hand either
+        assert!(process("32T3K 765xyz").is_err());
+    }
+
+    const ORDER: &str = "J23456789TQKA";
+
+    /// Strength of a hand's type, 0 for high card up to 6 for five of a kind
+    fn kind(cards: &str) -> u8 {
+        let mut counts = ORDER
+            .chars()
+            .map(|label| cards.chars().filter(|&c| c == label).count())
+            .filter(|&count| count > 0)
+            .collect::<Vec<_>>();
+        counts.sort();
+        counts.reverse();
+        match counts.as_slice() {
+            [5] => 6,
+            [4, 1] => 5,
+            [3, 2] => 4,
+            [3, 1, 1] => 3,
+            [2, 2, 1] => 2,
+            [2, 1, 1, 1] => 1,
+            _ => 0,
+        }
+    }
+
+    /// The best type over every label the jokers could stand for
+    fn kind_with_jokers(cards: &str) -> u8 {
+        ORDER
+            .chars()
+            .map(|label| kind(&cards.replace('J', &label.to_string())))
+            .max()
+            .unwrap()
+    }
+
+    /// Sort the hands by type, then card by card with jokers weakest, and sum rank times bid.
+    fn winnings(hands: &BTreeMap<String, u32>) -> u64 {
+        let mut sorted = hands.iter().collect::<Vec<_>>();
+        sorted.sort_by_key(|(cards, _)| {
+            let values = cards.chars().map(|c| ORDER.find(c)).collect::<Vec<_>>();
+            (kind_with_jokers(cards), values)
+        });
+        sorted
+            .iter()
+            .enumerate()
+            .map(|(i, (_, bid))| (i as u64 + 1) * **bid as u64)
+            .sum()
+    }
+
+    proptest! {
+        #[test]
+        fn process_ranks_with_jokers_by_explicit_sort(
+            hands in prop::collection::btree_map("[2-9TJQKA]{5}", 1..1000u32, 1..50),
+        ) {
+            let input = hands
+                .iter()
+                .map(|(cards, bid)| format!("{cards} {bid}"))
+                .collect::<Vec<_>>()
+                .join("\n");
+            prop_assert_eq!(winnings(&hands).to_string(), process(&input).unwrap());
+        }
+    }
 }
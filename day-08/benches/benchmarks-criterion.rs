@@ -26,10 +26,30 @@ fn criterion_benchmark_part2(c: &mut Criterion) {
     group.finish();
 }
 
+fn criterion_benchmark_walk(c: &mut Criterion) {
+    let input = include_str!("../input1.txt");
+    let network = network::Network::parse(input).unwrap();
+    let automaton = automaton::Automaton::new(&network, |node| node == "ZZZ").unwrap();
+
+    let mut group = c.benchmark_group("day_08::walk");
+    group.bench_function("network", |b| {
+        b.iter(|| network.steps("AAA", |node| node == "ZZZ").unwrap())
+    });
+    group.bench_function("automaton", |b| {
+        b.iter(|| automaton.steps(automaton.id("AAA").unwrap()).unwrap())
+    });
+    group.bench_function("compile", |b| {
+        b.iter(|| automaton::Automaton::new(&network, |node| node == "ZZZ").unwrap())
+    });
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     criterion_benchmark_part1,
-    criterion_benchmark_part2
+    criterion_benchmark_part2,
+    criterion_benchmark_walk
 );
 
 criterion_main!(benches);
@@ -0,0 +1,179 @@
+use std::cell::OnceCell;
+
+use crate::{
+    custom_error::AocError,
+    network::{Direction, Network},
+};
+
+/// A network compiled for walking: nodes are dense `u16` ids, transitions a flat table, and
+/// a node learns where the whole instruction string takes it, and whether a target is met on
+/// the way, the first time a walk needs it there.
+#[derive(Debug, Clone)]
+pub struct Automaton<'a> {
+    names: Vec<&'a str>,        // By id, sorted
+    transitions: Vec<u16>,      // Left at 2 * id, right at 2 * id + 1
+    instructions: Vec<u8>,      // 0 for left, 1 for right
+    is_target: Vec<bool>,       // By id
+    jumps: Vec<OnceCell<Jump>>, // By id
+}
+
+/// Where the instructions take a node: the node at their end, and the steps into them to the
+/// first target if any.
+type Jump = (u16, Option<u32>);
+
+impl<'a> Automaton<'a> {
+    /// Compile `network`, nodes accepted by `is_target` being where walks end.
+    pub fn new(network: &Network<'a>, is_target: impl Fn(&str) -> bool) -> Result<Self, AocError> {
+        let names = network.nodes().collect::<Vec<_>>();
+        // Ids run 0 to u16::MAX, so 65536 nodes still fit.
+        if names.len() > u16::MAX as usize + 1 {
+            return Err(AocError::TooManyNodes(names.len()));
+        }
+        let id = |name: &str| names.binary_search(&name).map(|id| id as u16);
+
+        let mut transitions = Vec::with_capacity(2 * names.len());
+        for name in &names {
+            for direction in [Direction::Left, Direction::Right] {
+                let next = network.next(name, direction)?;
+                transitions.push(id(next).map_err(|_| AocError::UnknownNode(next.to_string()))?);
+            }
+        }
+        let instructions = network
+            .instructions
+            .iter()
+            .map(|direction| match direction {
+                Direction::Left => 0,
+                Direction::Right => 1,
+            })
+            .collect::<Vec<_>>();
+        let is_target = names.iter().map(|name| is_target(name)).collect::<Vec<_>>();
+
+        Ok(Self {
+            jumps: vec![OnceCell::new(); names.len()],
+            names,
+            transitions,
+            instructions,
+            is_target,
+        })
+    }
+
+    /// The id of node `name`
+    pub fn id(&self, name: &str) -> Result<u16, AocError> {
+        self.names
+            .binary_search(&name)
+            .map(|id| id as u16)
+            .map_err(|_| AocError::UnknownNode(name.to_string()))
+    }
+
+    /// The name of node `id`
+    pub fn name(&self, id: u16) -> &'a str {
+        self.names[id as usize]
+    }
+
+    /// Follow the instructions, starting with the one at `offset`, from `from` to the first
+    /// target, and return the number of steps and that node. Single steps up to the start of the
+    /// instructions, then whole instruction strings at a time.
+    pub fn walk(&self, from: u16, offset: u64) -> Result<(u64, u16), AocError> {
+        let unreachable = || AocError::Unreachable(self.name(from).to_string());
+        let len = self.instructions.len() as u64;
+        if len == 0 {
+            return Err(unreachable());
+        }
+
+        let mut node = from;
+        let mut steps = 0u64;
+        while !(offset + steps).is_multiple_of(len) {
+            let direction = self.instructions[((offset + steps) % len) as usize];
+            node = self.transitions[2 * node as usize + direction as usize];
+            steps += 1;
+            if self.is_target[node as usize] {
+                return Ok((steps, node));
+            }
+        }
+
+        // Back at the start of the instructions on a node seen there before, it loops.
+        let mut seen = vec![false; self.names.len()];
+        loop {
+            let (end, first_hit) = self.jump(node);
+            if let Some(hit) = first_hit {
+                return Ok((steps + hit as u64, self.walk_steps(node, hit)));
+            }
+            if std::mem::replace(&mut seen[node as usize], true) {
+                return Err(unreachable());
+            }
+            node = end;
+            steps += len;
+        }
+    }
+
+    /// Number of steps from `from` to the first target
+    pub fn steps(&self, from: u16) -> Result<u64, AocError> {
+        self.walk(from, 0).map(|(steps, _)| steps)
+    }
+
+    /// Run the whole instruction string from `from`, once per node.
+    fn jump(&self, from: u16) -> Jump {
+        *self.jumps[from as usize].get_or_init(|| {
+            let mut node = from;
+            let mut hit = None;
+            for (step, direction) in self.instructions.iter().enumerate() {
+                node = self.transitions[2 * node as usize + *direction as usize];
+                if hit.is_none() && self.is_target[node as usize] {
+                    hit = Some(step as u32 + 1);
+                }
+            }
+            (node, hit)
+        })
+    }
+
+    /// Node reached by the first `count` instructions from `from`
+    fn walk_steps(&self, from: u16, count: u32) -> u16 {
+        self.instructions[..count as usize]
+            .iter()
+            .fold(from, |node, direction| {
+                self.transitions[2 * node as usize + *direction as usize]
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_walk() -> miette::Result<()> {
+        let network = Network::parse(
+            "LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)",
+        )?;
+        let automaton = Automaton::new(&network, |node| node == "ZZZ")?;
+        assert_eq!(2, automaton.id("ZZZ")?);
+        assert_eq!(6, automaton.steps(automaton.id("AAA")?)?);
+        // Single steps from the middle of the instructions
+        assert_eq!((2, 2), automaton.walk(automaton.id("AAA")?, 1)?);
+
+        let automaton = Automaton::new(&network, |node| node == "CCC")?;
+        assert!(automaton.steps(0).is_err());
+        Ok(())
+    }
+
+    /// As many nodes as u16 ids go round
+    #[test]
+    fn test_most_nodes() -> miette::Result<()> {
+        let mut input = "L\n\nAAA = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)".to_string();
+        for i in 0..u16::MAX as usize - 1 {
+            input += &format!("\nN{i}N = (N{i}N, N{i}N)");
+        }
+        let network = Network::parse(&input)?;
+        let automaton = Automaton::new(&network, |node| node == "ZZZ")?;
+        assert_eq!(1, automaton.steps(automaton.id("AAA")?)?);
+
+        input += "\nXXX = (XXX, XXX)";
+        let network = Network::parse(&input)?;
+        assert!(Automaton::new(&network, |node| node == "ZZZ").is_err());
+        Ok(())
+    }
+}
@@ -6,4 +6,25 @@ pub enum AocError {
     #[error(transparent)]
     #[diagnostic(code(aoc::io_error))]
     IoError(#[from] std::io::Error),
+    #[error("Network had bad format: {0}")]
+    #[diagnostic(code(aoc::bad_network))]
+    BadNetwork(String),
+    #[error("Network names node {0} but never defines it")]
+    #[diagnostic(code(aoc::unknown_node))]
+    UnknownNode(String),
+    #[error("Following the instructions from {0} never reaches the target")]
+    #[diagnostic(code(aoc::unreachable))]
+    Unreachable(String),
+    #[error("Network has {0} nodes, more than u16 ids can tell apart")]
+    #[diagnostic(code(aoc::too_many_nodes))]
+    TooManyNodes(usize),
+    #[error("Ghost starting at {0} doesn't walk a cycle through a single end node")]
+    #[diagnostic(code(aoc::not_periodic))]
+    NotPeriodic(String),
+    #[error("Network has no start node ending in A")]
+    #[diagnostic(code(aoc::no_starts))]
+    NoStarts,
+    #[error("Ghosts only meet after more steps than fit 64 bits")]
+    #[diagnostic(code(aoc::too_many_steps))]
+    TooManySteps,
 }
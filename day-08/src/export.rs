@@ -0,0 +1,165 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use crate::network::{Direction, Network};
+
+/// Node fill colours, starts green and ends red
+const START: &str = "palegreen";
+const END: &str = "lightcoral";
+
+/// One node of the drawing, standing for all nodes of its class when collapsed
+struct Vertex<'a> {
+    name: &'a str,
+    hidden: usize, // Nodes collapsed into this one
+}
+
+impl Vertex<'_> {
+    fn label(&self) -> String {
+        match self.hidden {
+            0 => self.name.to_string(),
+            n => format!("{} +{n}", self.name),
+        }
+    }
+
+    fn colour(&self) -> Option<&'static str> {
+        match self.name.as_bytes().last() {
+            Some(b'A') => Some(START),
+            Some(b'Z') => Some(END),
+            _ => None,
+        }
+    }
+}
+
+/// Vertices and labelled edges to draw, equivalent nodes merged into the first of their class
+/// when `collapse` is set.
+fn layout<'a>(
+    network: &Network<'a>,
+    collapse: bool,
+) -> (Vec<Vertex<'a>>, Vec<(&'a str, &'a str, &'static str)>) {
+    let classes = match collapse {
+        true => network.equivalence_classes(),
+        false => network.nodes().map(|node| vec![node]).collect(),
+    };
+    let representative = classes
+        .iter()
+        .flat_map(|class| class.iter().map(|node| (*node, class[0])))
+        .collect::<BTreeMap<_, _>>();
+
+    let vertices = classes
+        .iter()
+        .map(|class| Vertex {
+            name: class[0],
+            hidden: class.len() - 1,
+        })
+        .collect::<Vec<_>>();
+
+    let mut edges = vec![];
+    for vertex in &vertices {
+        let next = |direction| {
+            network
+                .next(vertex.name, direction)
+                .map(|node| representative[node])
+                .unwrap_or(vertex.name)
+        };
+        match (next(Direction::Left), next(Direction::Right)) {
+            (left, right) if left == right => edges.push((vertex.name, left, "L, R")),
+            (left, right) => {
+                edges.push((vertex.name, left, "L"));
+                edges.push((vertex.name, right, "R"));
+            }
+        }
+    }
+    (vertices, edges)
+}
+
+/// The network as a Graphviz digraph, node ids quoted as names like `11A` aren't valid ids
+pub fn to_dot(network: &Network, collapse: bool) -> String {
+    let (vertices, edges) = layout(network, collapse);
+
+    let mut dot = "digraph network {\n".to_string();
+    for vertex in &vertices {
+        let _ = match vertex.colour() {
+            Some(colour) => writeln!(
+                dot,
+                "    \"{}\" [label=\"{}\", style=filled, fillcolor={colour}];",
+                vertex.name,
+                vertex.label()
+            ),
+            None if vertex.hidden > 0 => {
+                writeln!(
+                    dot,
+                    "    \"{}\" [label=\"{}\"];",
+                    vertex.name,
+                    vertex.label()
+                )
+            }
+            None => Ok(()),
+        };
+    }
+    for (from, to, label) in edges {
+        let _ = writeln!(dot, "    \"{from}\" -> \"{to}\" [label=\"{label}\"];");
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// The network as a Mermaid flowchart, end nodes in class `goal` as `end` is a keyword
+pub fn to_mermaid(network: &Network, collapse: bool) -> String {
+    let (vertices, edges) = layout(network, collapse);
+
+    let mut mermaid = "flowchart LR\n".to_string();
+    for vertex in vertices.iter().filter(|v| v.hidden > 0) {
+        let _ = writeln!(mermaid, "    {}[\"{}\"]", vertex.name, vertex.label());
+    }
+    for (from, to, label) in edges {
+        let _ = writeln!(mermaid, "    {from} -->|{label}| {to}");
+    }
+    let _ = writeln!(mermaid, "    classDef start fill:{START}");
+    let _ = writeln!(mermaid, "    classDef goal fill:{END}");
+    for vertex in &vertices {
+        let _ = match vertex.colour() {
+            Some(START) => writeln!(mermaid, "    class {} start", vertex.name),
+            Some(_) => writeln!(mermaid, "    class {} goal", vertex.name),
+            None => Ok(()),
+        };
+    }
+    mermaid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collapse() -> miette::Result<()> {
+        // Two ghosts on identical cycles
+        let network = Network::parse(
+            "LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (XXX, 22Z)
+22Z = (22B, XXX)
+XXX = (XXX, XXX)",
+        )?;
+        assert_eq!(
+            "digraph network {
+    \"11A\" [label=\"11A +1\", style=filled, fillcolor=palegreen];
+    \"11B\" [label=\"11B +1\"];
+    \"11Z\" [label=\"11Z +1\", style=filled, fillcolor=lightcoral];
+    \"11A\" -> \"11B\" [label=\"L\"];
+    \"11A\" -> \"XXX\" [label=\"R\"];
+    \"11B\" -> \"XXX\" [label=\"L\"];
+    \"11B\" -> \"11Z\" [label=\"R\"];
+    \"11Z\" -> \"11B\" [label=\"L\"];
+    \"11Z\" -> \"XXX\" [label=\"R\"];
+    \"XXX\" -> \"XXX\" [label=\"L, R\"];
+}
+",
+            to_dot(&network, true)
+        );
+        assert!(to_mermaid(&network, false).starts_with("flowchart LR\n    11A -->|L| 11B\n"));
+        Ok(())
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::BTreeSet;
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use crate::part2::lcm;
+
+/// A random input with the answers it was built to have
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    pub input: String,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+/// Cycle lengths are these times the number of instructions.
+const MULTIPLES: [u64; 5] = [2, 3, 5, 7, 11];
+
+const GHOSTS: usize = 4;
+
+/// Largest `size` the generator goes up to
+pub const MAX_SIZE: usize = 1000;
+
+/// Characters of names, but the last of starts and ends
+const CHARS: &[u8] = b"BCDEFGHIJKLMNOPQRSTUVWXY0123456789";
+
+/// `size` instructions and a network the way the puzzle builds them: every ghost (AAA among
+/// them) walks a cycle of a prime multiple of `size` steps, ending on its Z node and following
+/// the instructions, while the other way out of every node leads into junk. That's up to
+/// 28 * `size` nodes, `size` is capped at `MAX_SIZE` for three character names to go round.
+pub fn generate(seed: u64, size: usize) -> Puzzle {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let len = size.clamp(1, MAX_SIZE);
+    let instructions = (0..len).map(|_| rng.gen_bool(0.5)).collect::<Vec<_>>(); // true for R
+    let mut names = BTreeSet::from(["AAA".to_string(), "ZZZ".to_string()]);
+
+    // Junk nodes lead to each other only.
+    let junk = (0..len)
+        .map(|_| new_name(&mut rng, &mut names, None))
+        .collect::<Vec<_>>();
+    let mut nodes = junk
+        .iter()
+        .map(|node| {
+            let left = junk[rng.gen_range(0..len)].clone();
+            (node.clone(), (left, junk[rng.gen_range(0..len)].clone()))
+        })
+        .collect::<Vec<_>>();
+
+    let mut multiples = MULTIPLES.to_vec();
+    multiples.shuffle(&mut rng);
+    let mut periods = vec![];
+    for (ghost, multiple) in multiples.into_iter().take(GHOSTS).enumerate() {
+        let period = multiple as usize * len;
+        let (start, end) = match ghost {
+            0 => ("AAA".to_string(), "ZZZ".to_string()),
+            _ => (
+                new_name(&mut rng, &mut names, Some('A')),
+                new_name(&mut rng, &mut names, Some('Z')),
+            ),
+        };
+        // Nodes after 0, 1, ... period steps, from the end it's around the cycle again.
+        let mut path = vec![start];
+        path.extend((1..period).map(|_| new_name(&mut rng, &mut names, None)));
+        path.push(end);
+        for step in 0..=period {
+            let next = path[if step == period { 1 } else { step + 1 }].clone();
+            let other = junk[rng.gen_range(0..len)].clone();
+            let lr = match instructions[step % len] {
+                false => (next, other),
+                true => (other, next),
+            };
+            nodes.push((path[step].clone(), lr));
+        }
+        periods.push(period as u64);
+    }
+    nodes.shuffle(&mut rng);
+
+    let mut input = instructions
+        .iter()
+        .map(|&right| if right { 'R' } else { 'L' })
+        .collect::<String>();
+    input.push_str("\n\n");
+    for (node, (left, right)) in nodes {
+        input.push_str(&format!("{node} = ({left}, {right})\n"));
+    }
+    let part2 = periods.iter().fold(1, |steps, &p| lcm(steps, p).unwrap());
+    Puzzle {
+        input,
+        part1: Some(periods[0].to_string()),
+        part2: Some(part2.to_string()),
+    }
+}
+
+/// A name not taken yet, ending with `last` if given and with neither 'A' nor 'Z' otherwise
+fn new_name(rng: &mut StdRng, names: &mut BTreeSet<String>, last: Option<char>) -> String {
+    loop {
+        let mut name = (0..3)
+            .map(|_| CHARS[rng.gen_range(0..CHARS.len())] as char)
+            .collect::<String>();
+        if let Some(last) = last {
+            name.replace_range(2.., &last.to_string());
+        }
+        if names.insert(name.clone()) {
+            return name;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate() -> miette::Result<()> {
+        for seed in 0..10 {
+            let puzzle = generate(seed, 50);
+            assert_eq!(puzzle.part1.unwrap(), crate::part1::process(&puzzle.input)?);
+            assert_eq!(puzzle.part2.unwrap(), crate::part2::process(&puzzle.input)?);
+        }
+        // Most names taken
+        let puzzle = generate(1, 1000);
+        assert_eq!(puzzle.part1.unwrap(), crate::part1::process(&puzzle.input)?);
+        Ok(())
+    }
+}
@@ -1,4 +1,8 @@
 pub mod custom_error;
 
+pub mod automaton;
+pub mod export;
+pub mod gen;
+pub mod network;
 pub mod part1;
 pub mod part2;
@@ -0,0 +1,360 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use nom::{
+    self,
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{self, alphanumeric1, line_ending, multispace1},
+    combinator::all_consuming,
+    multi::{many1, separated_list1},
+    sequence::{delimited, preceded, separated_pair, tuple},
+    IResult, Parser,
+};
+
+use crate::custom_error::AocError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+}
+
+/// The desert network: L/R instructions and every node's left and right neighbour.
+#[derive(Debug, Clone)]
+pub struct Network<'a> {
+    pub instructions: Vec<Direction>,
+    nodes: BTreeMap<&'a str, (&'a str, &'a str)>,
+}
+
+impl<'a> Network<'a> {
+    /// Parse the input, every node named on the right must be defined on a line of its own.
+    pub fn parse(input: &'a str) -> Result<Self, AocError> {
+        let (_, (instructions, nodes)) = all_consuming(network)(input.trim_end())
+            .map_err(|e| AocError::BadNetwork(e.to_string()))?;
+        let nodes = nodes.into_iter().collect::<BTreeMap<_, _>>();
+
+        if let Some(unknown) = nodes
+            .values()
+            .flat_map(|(left, right)| [left, right])
+            .find(|node| !nodes.contains_key(*node))
+        {
+            return Err(AocError::UnknownNode(unknown.to_string()));
+        }
+        Ok(Self {
+            instructions,
+            nodes,
+        })
+    }
+
+    /// All node names in order
+    pub fn nodes(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.nodes.keys().copied()
+    }
+
+    /// Where to go from `node` when told to go `direction`
+    pub fn next(&self, node: &str, direction: Direction) -> Result<&'a str, AocError> {
+        let (left, right) = self
+            .nodes
+            .get(node)
+            .ok_or_else(|| AocError::UnknownNode(node.to_string()))?;
+        Ok(match direction {
+            Direction::Left => left,
+            Direction::Right => right,
+        })
+    }
+
+    /// Ghosts start from all nodes ending with 'A'.
+    pub fn starts(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.nodes().filter(|node| node.ends_with('A'))
+    }
+
+    /// Nodes reachable from `from` (itself included) whatever the instructions say
+    pub fn reachable_from(&self, from: &str) -> Result<BTreeSet<&'a str>, AocError> {
+        let from = self.key(from)?;
+
+        let mut seen = BTreeSet::from([from]);
+        let mut stack = vec![from];
+        while let Some(node) = stack.pop() {
+            let (left, right) = self.nodes[node];
+            for next in [left, right] {
+                if seen.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        Ok(seen)
+    }
+
+    /// Nodes ending with 'Z' a ghost starting at `from` could reach
+    pub fn accepting_from(&self, from: &str) -> Result<BTreeSet<&'a str>, AocError> {
+        let mut reachable = self.reachable_from(from)?;
+        reachable.retain(|node| node.ends_with('Z'));
+        Ok(reachable)
+    }
+
+    /// Strongly connected components by Tarjan's algorithm, each sorted, in reverse topological
+    /// order (a component only leads to components before it).
+    pub fn strongly_connected_components(&self) -> Vec<Vec<&'a str>> {
+        let mut tarjan = Tarjan {
+            network: self,
+            index: BTreeMap::new(),
+            low: BTreeMap::new(),
+            stack: vec![],
+            on_stack: HashSet::new(),
+            components: vec![],
+        };
+        for node in self.nodes() {
+            if !tarjan.index.contains_key(node) {
+                tarjan.visit(node);
+            }
+        }
+        tarjan.components
+    }
+
+    /// Nodes no walk can tell apart: same kind (start, end or other) and equivalent left and
+    /// right neighbours. Classes are sorted and ordered by their first node.
+    pub fn equivalence_classes(&self) -> Vec<Vec<&'a str>> {
+        let kind = |node: &str| match node.as_bytes().last() {
+            Some(b'A') => 0,
+            Some(b'Z') => 1,
+            _ => 2,
+        };
+
+        // Refine classes by their neighbours' classes until nothing splits any more.
+        let mut class = self
+            .nodes()
+            .map(|node| (node, kind(node)))
+            .collect::<BTreeMap<_, usize>>();
+        let mut count = class.values().collect::<BTreeSet<_>>().len();
+        loop {
+            let mut signatures = BTreeMap::new();
+            let refined = self
+                .nodes
+                .iter()
+                .map(|(node, (left, right))| {
+                    let signature = (class[node], class[left], class[right]);
+                    let next = signatures.len();
+                    (*node, *signatures.entry(signature).or_insert(next))
+                })
+                .collect::<BTreeMap<_, _>>();
+            class = refined;
+            if signatures.len() == count {
+                break;
+            }
+            count = signatures.len();
+        }
+
+        let mut classes = BTreeMap::<usize, Vec<&str>>::new();
+        for (node, id) in class {
+            classes.entry(id).or_default().push(node);
+        }
+        let mut classes = classes.into_values().collect::<Vec<_>>();
+        classes.sort();
+        classes
+    }
+
+    /// Number of steps following the instructions from `from` to the first node accepted by
+    /// `is_target`.
+    pub fn steps(&self, from: &str, is_target: impl Fn(&str) -> bool) -> Result<u64, AocError> {
+        self.walk(from, 0, is_target).map(|(steps, _)| steps)
+    }
+
+    /// Follow the instructions, starting with the one at `offset`, from `from` to the first node
+    /// accepted by `is_target`, and return the number of steps and that node. A walk coming back
+    /// to a node at the same instruction without reaching a target never will.
+    pub fn walk(
+        &self,
+        from: &str,
+        offset: u64,
+        is_target: impl Fn(&str) -> bool,
+    ) -> Result<(u64, &'a str), AocError> {
+        let unreachable = || AocError::Unreachable(from.to_string());
+        if self.instructions.is_empty() {
+            return Err(unreachable());
+        }
+
+        let mut seen = HashSet::new();
+        let mut node = self.key(from)?;
+        let mut steps = 0u64;
+        loop {
+            let i = ((offset + steps) % self.instructions.len() as u64) as usize;
+            if steps > 0 && is_target(node) {
+                return Ok((steps, node));
+            }
+            if !seen.insert((node, i)) {
+                return Err(unreachable());
+            }
+            node = self.next(node, self.instructions[i])?;
+            steps += 1;
+        }
+    }
+
+    /// The node named `name`, borrowed from the input
+    fn key(&self, name: &str) -> Result<&'a str, AocError> {
+        self.nodes
+            .get_key_value(name)
+            .map(|(key, _)| *key)
+            .ok_or_else(|| AocError::UnknownNode(name.to_string()))
+    }
+}
+
+struct Tarjan<'n, 'a> {
+    network: &'n Network<'a>,
+    index: BTreeMap<&'a str, usize>,
+    low: BTreeMap<&'a str, usize>,
+    stack: Vec<&'a str>,
+    on_stack: HashSet<&'a str>,
+    components: Vec<Vec<&'a str>>,
+}
+
+impl<'n, 'a> Tarjan<'n, 'a> {
+    /// Depth first from `root` with an explicit call stack of (node, neighbours still to look
+    /// at), so long chains of nodes can't overflow the thread's stack.
+    fn visit(&mut self, root: &'a str) {
+        let mut calls = vec![(root, self.enter(root))];
+
+        while let Some((node, neighbours)) = calls.last_mut() {
+            let node = *node;
+            if let Some(next) = neighbours.next() {
+                if !self.index.contains_key(next) {
+                    calls.push((next, self.enter(next)));
+                } else if self.on_stack.contains(next) {
+                    let low = self.low[node].min(self.index[next]);
+                    self.low.insert(node, low);
+                }
+                continue;
+            }
+
+            calls.pop();
+            self.leave(node);
+            if let Some((caller, _)) = calls.last() {
+                let low = self.low[caller].min(self.low[node]);
+                self.low.insert(caller, low);
+            }
+        }
+    }
+
+    /// Number `node` and push it, returning its neighbours
+    fn enter(&mut self, node: &'a str) -> std::array::IntoIter<&'a str, 2> {
+        let index = self.index.len();
+        self.index.insert(node, index);
+        self.low.insert(node, index);
+        self.stack.push(node);
+        self.on_stack.insert(node);
+
+        let (left, right) = self.network.nodes[node];
+        [left, right].into_iter()
+    }
+
+    /// Once all of `node`'s neighbours are done: if it's the root of a component, pop it.
+    fn leave(&mut self, node: &'a str) {
+        if self.low[node] == self.index[node] {
+            let mut component = vec![];
+            while let Some(top) = self.stack.pop() {
+                self.on_stack.remove(top);
+                component.push(top);
+                if top == node {
+                    break;
+                }
+            }
+            component.sort();
+            self.components.push(component);
+        }
+    }
+}
+
+type Node<'a> = (&'a str, (&'a str, &'a str));
+
+fn network(i: &str) -> IResult<&str, (Vec<Direction>, Vec<Node<'_>>)> {
+    separated_pair(
+        many1(alt((
+            complete::char('L').map(|_| Direction::Left),
+            complete::char('R').map(|_| Direction::Right),
+        ))),
+        multispace1,
+        separated_list1(line_ending, node),
+    )(i)
+}
+
+/// Parse a line like `AAA = (BBB, CCC)`
+fn node(i: &str) -> IResult<&str, Node<'_>> {
+    tuple((
+        alphanumeric1,
+        preceded(
+            tag(" = "),
+            delimited(
+                tag("("),
+                separated_pair(alphanumeric1, tag(", "), alphanumeric1),
+                tag(")"),
+            ),
+        ),
+    ))(i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GHOSTS: &str = "LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)";
+
+    #[test]
+    fn test_analysis() -> miette::Result<()> {
+        let network = Network::parse(GHOSTS)?;
+        assert_eq!(vec!["11A", "22A"], network.starts().collect::<Vec<_>>());
+        assert_eq!(BTreeSet::from(["11Z"]), network.accepting_from("11A")?);
+        assert_eq!(
+            BTreeSet::from(["22B", "22C", "22Z"]),
+            network.reachable_from("22B")?
+        );
+        assert_eq!(
+            vec![
+                vec!["XXX"],
+                vec!["11B", "11Z"],
+                vec!["11A"],
+                vec!["22B", "22C", "22Z"],
+                vec!["22A"]
+            ],
+            network.strongly_connected_components()
+        );
+        // Ghosts' cycles differ in length, nothing to collapse.
+        assert_eq!(8, network.equivalence_classes().len());
+        Ok(())
+    }
+
+    /// A chain of nodes longer than recursion could walk on a test thread's stack
+    #[test]
+    fn test_long_chain_components() -> miette::Result<()> {
+        let len = 100_000;
+        let mut input = "L\n".to_string();
+        for i in 0..len {
+            let next = (i + 1).min(len - 1);
+            input += &format!("\nN{i}N = (N{next}N, N{next}N)");
+        }
+        let network = Network::parse(&input)?;
+        assert_eq!(len, network.strongly_connected_components().len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_steps() -> miette::Result<()> {
+        let network = Network::parse(GHOSTS)?;
+        assert_eq!(2, network.steps("11A", |node| node == "11Z")?);
+        assert_eq!(3, network.steps("22A", |node| node.ends_with('Z'))?);
+        // In the graph, but never on the instructions' way.
+        assert!(network.steps("XXX", |node| node == "11Z").is_err());
+
+        let network = Network::parse("RL\n\nAAA = (ZZZ, BBB)\nBBB = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)")?;
+        assert!(network.steps("AAA", |node| node == "ZZZ").is_err());
+        assert!(Network::parse("L\n\nAAA = (BBB, AAA)").is_err());
+        Ok(())
+    }
+}
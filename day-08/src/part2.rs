@@ -1,141 +1,46 @@
-use std::collections::BTreeMap;
+use tracing::{debug, info_span};
 
-use nom::{
-    self,
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::{self, alphanumeric1, multispace1},
-    multi::many1,
-    sequence::{delimited, preceded, separated_pair, tuple},
-    IResult, Parser,
-};
+use crate::{automaton::Automaton, custom_error::AocError, network::Network};
 
-use crate::custom_error::AocError;
-
-#[derive(Debug)]
-struct Automaton<'a> {
-    state_count: usize,   // Know with how many states in parallel we're dealing.
-    states: Vec<&'a str>, // This requires mutability.
-    i: usize,             // Index for reading
-    read_result: Vec<u32>,
-}
-
-impl<'a> Automaton<'a> {
-    /// Accept a reference to a (ordered) map of transitions.
-    fn new(transitions: &'a BTreeMap<&'a str, (&'a str, &'a str)>) -> Self {
-        // For initialization collect start states.
-        let states: Vec<&'a str> = dbg!(transitions
-            .keys()
-            .filter(|s| s.ends_with("A"))
-            .map(|s| *s)
-            .collect());
-
-        Self {
-            state_count: states.len(),
-            states,
-            i: 0,
-            read_result: vec![],
-        }
-    }
-
-    /// Reads a vector of Left and Right and applies it to a collection of states/transitions.
-    /// repeats reading until all transitions reach an accepting state in parallel. Returns
-    /// Iterator.
-    fn read<'b: 'a>(
-        &mut self,
-        path: Vec<Direction>,
-        transitions: &'b BTreeMap<&'b str, (&'b str, &'b str)>,
-    ) -> std::slice::Iter<'_, u32> {
-        assert!(path.len() > 0);
-        let mut iterations = 0u32;
-
-        use Direction::*;
-        while self.states.iter().filter(|s| s.ends_with("Z")).count() < self.state_count {
-            // Accumulate transition count.
-            iterations += 1;
-
-            let mut working_states: Vec<&str> = vec![];
-
-            for (no, state) in self.states.iter().enumerate() {
-                let next = transitions.get(state).unwrap();
-
-                let next_state = match path[self.i] {
-                    Left => next.0,
-                    Right => next.1,
-                };
-
-                // println!("{state} -> {next_state}",);
-                if next_state.ends_with("Z") {
-                    println!("{next_state} reached by {no} in iteration {iterations}")
-                }
-
-                working_states.push(&next_state);
-            }
-            // Transitions now become new states.
-            self.states = working_states;
-
-            // Update index
-            self.i = if self.i == path.len() - 1 {
-                0
-            } else {
-                self.i + 1
-            };
-        }
-        // Return value
-        self.read_result.push(iterations);
-        self.read_result.iter()
+/// Steps for a ghost from `start` to its first end node, when it keeps returning to that node
+/// after as many steps again. These cycles make all ghosts meet at the least common multiple.
+fn period(automaton: &Automaton, start: u16) -> Result<u64, AocError> {
+    let (steps, end) = automaton.walk(start, 0)?;
+    if automaton.walk(end, steps)? != (steps, end) {
+        return Err(AocError::NotPeriodic(automaton.name(start).to_string()));
     }
+    Ok(steps)
 }
 
-#[tracing::instrument]
-pub fn process(input: &str) -> miette::Result<String, AocError> {
-    let (input, path) = get_path(input).unwrap();
-    let transitions: BTreeMap<&str, (&str, &str)> = input
-        .lines()
-        .map(|line| {
-            let (_, st) = get_transition(line).unwrap();
-            st
-        })
-        .collect();
-
-    let mut walker = Automaton::new(&transitions);
-    Ok(walker.read(path, &transitions).sum::<u32>().to_string())
-}
-
-#[derive(Debug)]
-enum Direction {
-    Left,
-    Right,
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
 }
 
-// A nicer parser. This one is inpired by Chris Biscardi.
-fn get_path(input: &str) -> IResult<&str, Vec<Direction>> {
-    let (i, (path, _)) = tuple((
-        many1(alt((
-            complete::char('L').map(|_| Direction::Left),
-            complete::char('R').map(|_| Direction::Right),
-        ))),
-        multispace1,
-    ))(input)?;
-
-    Ok((i, path))
+/// Least common multiple, `None` if it doesn't fit 64 bits
+pub(crate) fn lcm(a: u64, b: u64) -> Option<u64> {
+    (a / gcd(a, b)).checked_mul(b)
 }
 
-// The only change here is to alphanumeric1.
-fn get_transition(line: &str) -> IResult<&str, (&str, (&str, &str))> {
-    let (_, (state, lr)) = tuple((
-        alphanumeric1,
-        preceded(
-            tag(" = "),
-            delimited(
-                tag("("),
-                separated_pair(alphanumeric1, tag(", "), alphanumeric1),
-                tag(")"),
-            ),
-        ),
-    ))(line)?;
+#[tracing::instrument(skip(input))]
+pub fn process(input: &str) -> miette::Result<String, AocError> {
+    let network = info_span!("parse").in_scope(|| Network::parse(input))?;
+    let _solve = info_span!("solve").entered();
+    let automaton = Automaton::new(&network, |node| node.ends_with('Z'))?;
+    if network.starts().next().is_none() {
+        return Err(AocError::NoStarts);
+    }
 
-    Ok(("", (state, lr)))
+    let mut steps = 1u64;
+    for start in network.starts() {
+        let period = period(&automaton, automaton.id(start)?)?;
+        debug!(start, period, "ghost cycle");
+        steps = lcm(steps, period).ok_or(AocError::TooManySteps)?;
+    }
+    Ok(steps.to_string())
 }
 
 #[cfg(test)]
@@ -154,7 +59,32 @@ mod tests {
 22C = (22Z, 22Z)
 22Z = (22B, 22B)
 XXX = (XXX, XXX)";
-        assert_eq!("six", process(input)?);
+        assert_eq!("6", process(input)?);
         Ok(())
     }
+
+    #[test]
+    fn test_no_starts() {
+        let input = "L\n\n11B = (11Z, 11Z)\n11Z = (11B, 11B)";
+        assert!(matches!(process(input), Err(AocError::NoStarts)));
+    }
+
+    /// Ghosts with coprime periods only meet past `u64::MAX`
+    #[test]
+    fn test_too_many_steps() {
+        let mut input = "L\n".to_string();
+        for (ghost, period) in [601, 607, 613, 617, 619, 631, 641].into_iter().enumerate() {
+            let node = |i: u32| match i {
+                0 => format!("G{ghost}A"),
+                i if i == period => format!("G{ghost}Z"),
+                i => format!("G{ghost}N{i}"),
+            };
+            for i in 0..period {
+                let next = node(i + 1);
+                input += &format!("\n{} = ({next}, {next})", node(i));
+            }
+            input += &format!("\n{} = ({}, {})", node(period), node(1), node(1));
+        }
+        assert!(matches!(process(&input), Err(AocError::TooManySteps)));
+    }
 }
@@ -1,16 +1,14 @@
-use criterion::{
-    criterion_group, criterion_main, Criterion,
-};
+use criterion::{criterion_group, criterion_main, Criterion};
 
 use day_09::*;
 
+mod reference;
+
 fn criterion_benchmark_part1(c: &mut Criterion) {
     let input = include_str!("../input1.txt");
 
     let mut group = c.benchmark_group("day_09::part1");
-    group.bench_with_input("part1", input, |b, input| {
-        b.iter(|| part1::process(input))
-    });
+    group.bench_with_input("part1", input, |b, input| b.iter(|| part1::process(input)));
 
     group.finish();
 }
@@ -19,8 +17,29 @@ fn criterion_benchmark_part2(c: &mut Criterion) {
     let input = include_str!("../input2.txt");
 
     let mut group = c.benchmark_group("day_09::part2");
-    group.bench_with_input("part2", input, |b, input| {
-        b.iter(|| part2::process(input))
+    group.bench_with_input("part2", input, |b, input| b.iter(|| part2::process(input)));
+
+    group.finish();
+}
+
+fn criterion_benchmark_extrapolate(c: &mut Criterion) {
+    let input = include_str!("../input1.txt");
+
+    let mut group = c.benchmark_group("day_09::extrapolate");
+    group.bench_with_input("engine", input, |b, input| {
+        b.iter(|| engine::predict_all(input).unwrap().next)
+    });
+    group.bench_with_input("sequence", input, |b, input| {
+        b.iter(|| {
+            sequence::parse_sequences(input)
+                .unwrap()
+                .iter()
+                .map(|sequence| sequence.forward(1).unwrap())
+                .sum::<i128>()
+        })
+    });
+    group.bench_with_input("recursive", input, |b, input| {
+        b.iter(|| reference::process(input))
     });
 
     group.finish();
@@ -29,7 +48,8 @@ fn criterion_benchmark_part2(c: &mut Criterion) {
 criterion_group!(
     benches,
     criterion_benchmark_part1,
-    criterion_benchmark_part2
+    criterion_benchmark_part2,
+    criterion_benchmark_extrapolate
 );
 
 criterion_main!(benches);
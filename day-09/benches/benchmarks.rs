@@ -1,5 +1,7 @@
 use day_09::*;
 
+mod reference;
+
 fn main() {
     // Run registered benchmarks.
     divan::main();
@@ -7,16 +9,36 @@ fn main() {
 
 #[divan::bench]
 fn part1() {
-    part1::process(divan::black_box(include_str!(
-        "../input1.txt",
-    )))
-    .unwrap();
+    part1::process(divan::black_box(include_str!("../input1.txt",))).unwrap();
 }
 
 #[divan::bench]
 fn part2() {
-    part2::process(divan::black_box(include_str!(
-        "../input2.txt",
-    )))
-    .unwrap();
+    part2::process(divan::black_box(include_str!("../input2.txt",))).unwrap();
+}
+
+/// Next values by each implementation, the engine being what part 1 runs
+mod extrapolate {
+    use super::*;
+
+    const INPUT: &str = include_str!("../input1.txt");
+
+    #[divan::bench]
+    fn engine() -> i64 {
+        engine::predict_all(divan::black_box(INPUT)).unwrap().next
+    }
+
+    #[divan::bench]
+    fn sequence() -> i128 {
+        sequence::parse_sequences(divan::black_box(INPUT))
+            .unwrap()
+            .iter()
+            .map(|sequence| sequence.forward(1).unwrap())
+            .sum()
+    }
+
+    #[divan::bench]
+    fn recursive() -> i32 {
+        reference::process(divan::black_box(INPUT))
+    }
 }
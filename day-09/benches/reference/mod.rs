@@ -0,0 +1,39 @@
+//! The original recursive extrapolation, kept as a baseline for the benches.
+
+use itertools::Itertools;
+
+fn extrapolate(v_tup_int: Vec<i32>) -> Vec<i32> {
+    if v_tup_int.iter().all(|i| *i == 0i32) {
+        // Termination
+        vec![0]
+    } else {
+        // Build a vector of pairwise differences and pass it down in the recursion.
+        let v_results = extrapolate(
+            v_tup_int
+                .iter()
+                .tuple_windows()
+                .map(|(a, b)| b - a)
+                .collect::<Vec<i32>>(),
+        );
+        // Then add the value just extrapolated by recursion and rightmost in the current row...
+        let res = v_results.last().unwrap() + v_tup_int.last().unwrap();
+        let mut v_results = v_results;
+        // ... and append the sum to results returned.
+        v_results.push(res);
+        v_results
+    }
+}
+
+/// Sum of the next values, as part 1 used to compute it
+pub fn process(input: &str) -> i32 {
+    input
+        .lines()
+        .map(|line| {
+            let v_tup_int = line
+                .split_ascii_whitespace()
+                .map(|n| n.parse().unwrap())
+                .collect();
+            *extrapolate(v_tup_int).last().unwrap()
+        })
+        .sum()
+}
@@ -6,4 +6,22 @@ pub enum AocError {
     #[error(transparent)]
     #[diagnostic(code(aoc::io_error))]
     IoError(#[from] std::io::Error),
+    #[error("Line should hold numbers separated by spaces, got '{0}'")]
+    #[diagnostic(code(aoc::bad_line))]
+    BadLine(String),
+    #[error("Sequence needs at least one value")]
+    #[diagnostic(code(aoc::empty_sequence))]
+    EmptySequence,
+    #[error("Difference table overflows i128")]
+    #[diagnostic(code(aoc::table_overflow))]
+    TableOverflow,
+    #[error("Extrapolation overflows i128 at index {0}")]
+    #[diagnostic(code(aoc::overflow))]
+    Overflow(i128),
+    #[error("Difference table overflows i64, use a `Sequence` for exact values")]
+    #[diagnostic(code(aoc::engine_overflow))]
+    EngineOverflow,
+    #[error("History should hold at most {0} values")]
+    #[diagnostic(code(aoc::too_long))]
+    TooLong(usize),
 }
@@ -0,0 +1,162 @@
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::custom_error::AocError;
+
+/// Longest history the engine takes, puzzle histories hold 21 values.
+pub const MAX_LEN: usize = 64;
+
+/// The values just before and just after a history
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Prediction {
+    pub previous: i64,
+    pub next: i64,
+}
+
+impl Prediction {
+    fn checked_add(self, other: Self) -> Result<Self, AocError> {
+        let overflow = || AocError::EngineOverflow;
+        Ok(Self {
+            previous: self
+                .previous
+                .checked_add(other.previous)
+                .ok_or_else(overflow)?,
+            next: self.next.checked_add(other.next).ok_or_else(overflow)?,
+        })
+    }
+}
+
+/// Extrapolate both ways by building the difference table in place, `values` is left holding
+/// garbage.
+///
+/// Each pass replaces `values[..len]` by its pairwise differences in `values[..len - 1]`, so the
+/// last value of every row stays behind at `values[len - 1]`. The next value is the sum of these
+/// last values, the previous one the alternating sum of the first values, collected on the way
+/// down.
+pub fn extrapolate(values: &mut [i64]) -> Result<Prediction, AocError> {
+    let overflow = || AocError::EngineOverflow;
+
+    let mut previous = 0i64;
+    let mut sign = 1i64;
+    let mut len = values.len();
+    while len > 0 && values[..len].iter().any(|v| *v != 0) {
+        previous = values[0]
+            .checked_mul(sign)
+            .and_then(|first| previous.checked_add(first))
+            .ok_or_else(overflow)?;
+        sign = -sign;
+
+        for i in 0..len - 1 {
+            values[i] = values[i + 1].checked_sub(values[i]).ok_or_else(overflow)?;
+        }
+        len -= 1;
+    }
+
+    let next = values[len..]
+        .iter()
+        .try_fold(0i64, |next, last| next.checked_add(*last))
+        .ok_or_else(overflow)?;
+    Ok(Prediction { previous, next })
+}
+
+/// Extrapolate every history of `input` and sum the predictions, without allocating.
+#[cfg(not(feature = "parallel"))]
+#[tracing::instrument(skip(input))]
+pub fn predict_all(input: &str) -> Result<Prediction, AocError> {
+    let mut buffer = [0i64; MAX_LEN];
+    input.lines().try_fold(Prediction::default(), |sum, line| {
+        sum.checked_add(predict(line, &mut buffer)?)
+    })
+}
+
+/// Extrapolate every history of `input` on the rayon pool and sum the predictions.
+#[cfg(feature = "parallel")]
+#[tracing::instrument(skip(input))]
+pub fn predict_all(input: &str) -> Result<Prediction, AocError> {
+    input
+        .par_lines()
+        .map(|line| predict(line, &mut [0i64; MAX_LEN]))
+        .try_reduce(Prediction::default, Prediction::checked_add)
+}
+
+/// Check every line of `input` is a history the engine takes, parsing like `predict_all` does.
+pub fn check(input: &str) -> Result<(), AocError> {
+    let mut buffer = [0i64; MAX_LEN];
+    for line in input.lines() {
+        if parse_into(line, &mut buffer)? == 0 {
+            return Err(AocError::EmptySequence);
+        }
+    }
+    Ok(())
+}
+
+/// Extrapolate the history on `line`, holding its values in `buffer`.
+fn predict(line: &str, buffer: &mut [i64; MAX_LEN]) -> Result<Prediction, AocError> {
+    let _history = tracing::trace_span!("history", line).entered();
+    let len = parse_into(line, buffer)?;
+    if len == 0 {
+        return Err(AocError::EmptySequence);
+    }
+    let prediction = extrapolate(&mut buffer[..len])?;
+    tracing::trace!(?prediction);
+    Ok(prediction)
+}
+
+/// Parse the numbers of `line` into `buffer` and return how many there are.
+fn parse_into(line: &str, buffer: &mut [i64; MAX_LEN]) -> Result<usize, AocError> {
+    let mut len = 0;
+    for word in line.split_ascii_whitespace() {
+        if len == MAX_LEN {
+            return Err(AocError::TooLong(MAX_LEN));
+        }
+        buffer[len] = word
+            .parse()
+            .map_err(|_| AocError::BadLine(line.to_string()))?;
+        len += 1;
+    }
+    Ok(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sequence::Sequence;
+
+    #[test]
+    fn test_extrapolate() -> miette::Result<()> {
+        for history in [
+            &[0, 3, 6, 9, 12, 15][..],
+            &[1, 3, 6, 10, 15, 21],
+            &[10, 13, 16, 21, 30, 45],
+            &[0, 0, 0],
+            &[7],
+            &[-4, 9, -2, 15, 1],
+        ] {
+            let sequence = Sequence::new(history)?;
+            let prediction = extrapolate(&mut history.to_vec())?;
+            assert_eq!(sequence.backward(1)?, prediction.previous as i128);
+            assert_eq!(sequence.forward(1)?, prediction.next as i128);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_predict_all() -> miette::Result<()> {
+        let input = "0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45";
+        assert_eq!(
+            Prediction {
+                previous: 2,
+                next: 114
+            },
+            predict_all(input)?
+        );
+        assert!(predict_all("1 2 x").is_err());
+        assert!(predict_all(&"1 ".repeat(MAX_LEN + 1)).is_err());
+        assert!(predict_all(&format!("0 {}", i64::MAX)).is_err());
+        assert!(check(input).is_ok());
+        assert!(check("1 2\n\n3").is_err());
+        Ok(())
+    }
+}
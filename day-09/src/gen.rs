@@ -0,0 +1,78 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// A random input with the answers it was built to have
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    pub input: String,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+/// `size` histories of 5 to 21 values, each from a polynomial of a known degree (up to 8, and
+/// two below the length so the differences reach zero) with integer values everywhere:
+/// `p(x) = sum of c_k * binomial(x, k)` for small random `c_k`.
+pub fn generate(seed: u64, size: usize) -> Puzzle {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut input = String::new();
+    let (mut part1, mut part2) = (0i64, 0i64);
+
+    for _ in 0..size {
+        let len = rng.gen_range(5..=21);
+        let degree = rng.gen_range(0..=(len - 2).min(8));
+        let mut coefficients = (0..=degree)
+            .map(|_| rng.gen_range(-10..=10))
+            .collect::<Vec<i64>>();
+        if coefficients[degree] == 0 {
+            coefficients[degree] = 1; // Keep the degree
+        }
+        let p = |x: i64| -> i64 {
+            coefficients
+                .iter()
+                .enumerate()
+                .map(|(k, c)| c * binomial(x, k))
+                .sum()
+        };
+
+        let values = (0..len as i64)
+            .map(|x| p(x).to_string())
+            .collect::<Vec<_>>();
+        input.push_str(&values.join(" "));
+        input.push('\n');
+        part1 += p(len as i64);
+        part2 += p(-1);
+    }
+
+    Puzzle {
+        input,
+        part1: Some(part1.to_string()),
+        part2: Some(part2.to_string()),
+    }
+}
+
+/// `x` choose `k` for any integer `x`, like `binomial(-1, k) = (-1)^k`
+fn binomial(x: i64, k: usize) -> i64 {
+    (0..k as i64).fold(1, |acc, i| acc * (x - i) / (i + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_binomial() {
+        assert_eq!(10, binomial(5, 2));
+        assert_eq!(0, binomial(2, 3));
+        assert_eq!(-1, binomial(-1, 3));
+        assert_eq!(6, binomial(-3, 2));
+    }
+
+    #[test]
+    fn test_generate() -> miette::Result<()> {
+        for seed in 0..20 {
+            let puzzle = generate(seed, 100);
+            assert_eq!(puzzle.part1.unwrap(), crate::part1::process(&puzzle.input)?);
+            assert_eq!(puzzle.part2.unwrap(), crate::part2::process(&puzzle.input)?);
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
 pub mod custom_error;
 
+pub mod engine;
+pub mod gen;
 pub mod part1;
 pub mod part2;
+pub mod sequence;
@@ -1,49 +1,13 @@
-use itertools::Itertools;
-use nom::{
-    self,
-    character::complete::{self, space1},
-    multi::separated_list1,
-    IResult,
-};
+use tracing::info_span;
 
-use crate::custom_error::AocError;
+use crate::{custom_error::AocError, engine::predict_all};
 
-fn extrapolate(v_tup_int: Vec<i32>) -> Vec<i32> {
-    if v_tup_int.iter().all(|i| *i == 0i32) {
-        // Termination
-        vec![0]
-    } else {
-        // Build a vector of pairwise differences and pass it down in the recursion.
-        let v_results = extrapolate(
-            v_tup_int
-                .iter()
-                .tuple_windows()
-                .map(|(a, b)| b - a)
-                .collect::<Vec<i32>>(),
-        );
-        // Then add the value just extrapolated by recursion and rightmost in the current row...
-        let res = v_results.last().unwrap() + v_tup_int.last().unwrap();
-        let mut v_results = v_results;
-        // ... and append the sum to results returned.
-        v_results.push(res);
-        v_results
-    }
-}
-
-#[tracing::instrument]
+#[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String, AocError> {
-    Ok(input
-        .lines()
-        .map(|line| {
-            let (_, v_tup_int) = parse(line).unwrap();
-            extrapolate(v_tup_int).last().unwrap().clone()
-        })
-        .sum::<i32>()
-        .to_string())
-}
-
-fn parse(i: &str) -> IResult<&str, Vec<i32>> {
-    Ok(separated_list1(space1, complete::i32)(i)?)
+    // Predict the next value of every history. Lines are parsed one by one into the engine's
+    // buffer, so there's no parse phase of its own.
+    let _solve = info_span!("solve").entered();
+    Ok(predict_all(input)?.next.to_string())
 }
 
 #[cfg(test)]
@@ -0,0 +1,156 @@
+//! Exact extrapolation any number of steps either way, in i128 with overflow detection.
+//!
+//! The parts use the faster `engine`, which only predicts one step each way in i64. This module
+//! stays for what the engine can't do, like the value 1,000,000 steps ahead, and as the oracle
+//! the engine is tested and benched against.
+
+use nom::{
+    self,
+    character::complete::{self, space1},
+    combinator::all_consuming,
+    multi::separated_list1,
+    IResult,
+};
+
+use crate::custom_error::AocError;
+
+/// An OASIS history, seen as the values of a polynomial at 0, 1, 2, ...
+///
+/// The difference table is built once. Its leading entries (Newton's forward differences) are
+/// all we keep, as `f(n) = sum over k of binomial(n, k) * difference[k]` holds for any integer
+/// `n`, ahead of the history as well as before it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sequence {
+    differences: Vec<i128>, // Leading entry of every row of the table, down to the last non-zero row
+    len: usize,
+}
+
+impl Sequence {
+    pub fn new(values: &[i64]) -> Result<Self, AocError> {
+        if values.is_empty() {
+            return Err(AocError::EmptySequence);
+        }
+
+        let mut row = values.iter().map(|v| *v as i128).collect::<Vec<_>>();
+        let mut differences = vec![];
+
+        // Replace the row by its pairwise differences until all are zero (or one is left).
+        for len in (1..=row.len()).rev() {
+            if row[..len].iter().all(|v| *v == 0) {
+                break;
+            }
+            differences.push(row[0]);
+            for i in 0..len - 1 {
+                row[i] = row[i + 1]
+                    .checked_sub(row[i])
+                    .ok_or(AocError::TableOverflow)?;
+            }
+        }
+        if differences.is_empty() {
+            differences.push(0); // All zeros
+        }
+
+        Ok(Self {
+            differences,
+            len: values.len(),
+        })
+    }
+
+    /// Degree of the polynomial, a history shorter than the degree + 2 can't tell it for sure.
+    pub fn degree(&self) -> usize {
+        self.differences.len() - 1
+    }
+
+    /// Number of values in the history
+    pub fn history_len(&self) -> usize {
+        self.len
+    }
+
+    /// Value at `index`, where the history is at 0, 1, ... len - 1
+    pub fn at(&self, index: i128) -> Result<i128, AocError> {
+        let overflow = || AocError::Overflow(index);
+
+        let mut value = 0i128;
+        let mut binomial = 1i128; // binomial(index, k), also for negative indices
+        for (k, difference) in self.differences.iter().enumerate() {
+            if k > 0 {
+                binomial = binomial
+                    .checked_mul(index - (k as i128 - 1))
+                    .ok_or_else(overflow)?
+                    / k as i128;
+            }
+            value = binomial
+                .checked_mul(*difference)
+                .and_then(|term| value.checked_add(term))
+                .ok_or_else(overflow)?;
+        }
+        Ok(value)
+    }
+
+    /// Value `steps` ahead of the last value in the history
+    pub fn forward(&self, steps: u64) -> Result<i128, AocError> {
+        self.at(self.len as i128 - 1 + steps as i128)
+    }
+
+    /// Value `steps` before the first value in the history
+    pub fn backward(&self, steps: u64) -> Result<i128, AocError> {
+        self.at(-(steps as i128))
+    }
+}
+
+/// Parse one history per line
+pub fn parse_sequences(input: &str) -> Result<Vec<Sequence>, AocError> {
+    input
+        .lines()
+        .map(|line| {
+            let (_, values) =
+                all_consuming(parse)(line).map_err(|_| AocError::BadLine(line.to_string()))?;
+            Sequence::new(&values)
+        })
+        .collect()
+}
+
+fn parse(i: &str) -> IResult<&str, Vec<i64>> {
+    separated_list1(space1, complete::i64)(i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_degree() -> miette::Result<()> {
+        assert_eq!(0, Sequence::new(&[0, 0, 0])?.degree());
+        assert_eq!(0, Sequence::new(&[7, 7, 7])?.degree());
+        assert_eq!(1, Sequence::new(&[0, 3, 6, 9, 12, 15])?.degree());
+        assert_eq!(2, Sequence::new(&[1, 3, 6, 10, 15, 21])?.degree());
+        assert_eq!(3, Sequence::new(&[10, 13, 16, 21, 30, 45])?.degree());
+        // Too short to tell, the last row is taken as constant.
+        assert_eq!(1, Sequence::new(&[1, 4])?.degree());
+        assert!(Sequence::new(&[]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_extrapolate() -> miette::Result<()> {
+        // n^2 + 1 at 0..5
+        let squares = Sequence::new(&[1, 2, 5, 10, 17])?;
+        assert_eq!(26, squares.forward(1)?);
+        assert_eq!(2, squares.backward(1)?);
+        assert_eq!(1_000_008_000_017, squares.forward(1_000_000)?);
+        assert_eq!(1_000_000_000_001, squares.backward(1_000_000)?);
+
+        let cubes = Sequence::new(&[10, 13, 16, 21, 30, 45])?;
+        assert_eq!(68, cubes.forward(1)?);
+        assert_eq!(5, cubes.backward(1)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_overflow() -> miette::Result<()> {
+        let steep = Sequence::new(&[0, 1, 32, 243, 1024, 3125, 7776])?; // n^5
+        assert!(steep.forward(1 << 20)?.is_positive());
+        assert!(steep.forward(1 << 40).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+//! Print the steps from `S` to every tile connected to it, e.g.
+//!
+//!     cargo run -p day-10 --bin distances
+//!     cargo run -p day-10 --bin distances -- --csv > distances.csv
+//!
+//! As text, the last digit of the distance is drawn over the maze. The farthest tile goes to
+//! stderr.
+use day_10::maze::PipeMaze;
+use miette::miette;
+
+fn main() -> miette::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let csv = match std::env::args().nth(1).as_deref() {
+        None => false,
+        Some("--csv") => true,
+        Some(_) => return Err(miette!("Usage: distances [--csv]")),
+    };
+
+    let maze = PipeMaze::parse(include_str!("../../input1.txt"))?;
+    let distances = maze.distances();
+    if csv {
+        print!("{}", distances.to_csv());
+    } else {
+        print!("{}", distances.to_text(&maze));
+    }
+    if let Some((row, col, distance)) = distances.farthest() {
+        eprintln!("Farthest tile at row {row}, column {col}: {distance} steps");
+    }
+    Ok(())
+}
@@ -6,4 +6,22 @@ pub enum AocError {
     #[error(transparent)]
     #[diagnostic(code(aoc::io_error))]
     IoError(#[from] std::io::Error),
+    #[error("Maze line {0} is {1} tiles wide, expected {2}")]
+    #[diagnostic(code(aoc::ragged_maze))]
+    RaggedMaze(usize, usize, usize),
+    #[error("Unknown tile '{0}' at line {1}, column {2}")]
+    #[diagnostic(code(aoc::bad_tile))]
+    BadTile(char, usize, usize),
+    #[error("Maze has no start tile 'S'")]
+    #[diagnostic(code(aoc::no_start))]
+    NoStart,
+    #[error("Maze has more than one start tile 'S'")]
+    #[diagnostic(code(aoc::many_starts))]
+    ManyStarts,
+    #[error("Start tile connects to {0} pipes, no two of them closing a loop")]
+    #[diagnostic(code(aoc::ambiguous_start))]
+    AmbiguousStart(usize),
+    #[error("Loop breaks at row {0}, column {1}")]
+    #[diagnostic(code(aoc::broken_loop))]
+    BrokenLoop(usize, usize),
 }
@@ -0,0 +1,129 @@
+use std::{collections::VecDeque, fmt::Write};
+
+use crate::maze::{opposite, PipeMaze, EAST, NORTH, SOUTH, WEST};
+
+/// Steps from `S` to every tile reachable through connected pipes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistanceMap {
+    distances: Vec<Option<u32>>, // Row after row, `None` where unreachable
+    width: usize,
+}
+
+impl DistanceMap {
+    /// Breadth first search from `S`, moving between tiles connecting to each other.
+    pub fn new(maze: &PipeMaze) -> Self {
+        let width = maze.width();
+        let mut distances = vec![None; width * maze.height()];
+        let mut queue = VecDeque::from([(maze.start(), 0)]);
+        distances[maze.start().0 * width + maze.start().1] = Some(0);
+
+        while let Some(((row, col), distance)) = queue.pop_front() {
+            let sides = maze.connections(row, col);
+            for side in [NORTH, EAST, SOUTH, WEST] {
+                if sides & side == 0 {
+                    continue;
+                }
+                let Some((r, c)) = maze.step(row, col, side) else {
+                    continue;
+                };
+                let seen = &mut distances[r * width + c];
+                if seen.is_none() && maze.connections(r, c) & opposite(side) != 0 {
+                    *seen = Some(distance + 1);
+                    queue.push_back(((r, c), distance + 1));
+                }
+            }
+        }
+
+        Self { distances, width }
+    }
+
+    /// Steps from `S` to (row, col), `None` if it can't be reached
+    pub fn get(&self, row: usize, col: usize) -> Option<u32> {
+        if col < self.width {
+            self.distances.get(row * self.width + col).copied().flatten()
+        } else {
+            None
+        }
+    }
+
+    /// Reachable tiles in reading order as `(row, col, distance)`
+    pub fn reachable(&self) -> impl Iterator<Item = (usize, usize, u32)> + '_ {
+        self.distances
+            .iter()
+            .enumerate()
+            .filter_map(|(i, d)| d.map(|d| (i / self.width, i % self.width, d)))
+    }
+
+    /// The farthest tile as `(row, col, distance)`, the first in reading order on ties
+    pub fn farthest(&self) -> Option<(usize, usize, u32)> {
+        self.reachable()
+            .reduce(|far, tile| if tile.2 > far.2 { tile } else { far })
+    }
+
+    /// The maze with the last digit of the distance over every reachable tile, like:
+    ///
+    /// ```text
+    ///     ..45.
+    ///     .236.
+    ///     01.78
+    ///     14567
+    ///     23...
+    /// ```
+    pub fn to_text(&self, maze: &PipeMaze) -> String {
+        let mut text = String::with_capacity(self.distances.len() + maze.height());
+        for row in 0..maze.height() {
+            for col in 0..maze.width() {
+                text.push(match self.get(row, col) {
+                    Some(d) => char::from_digit(d % 10, 10).unwrap_or('?'),
+                    None => maze.tile_at(row, col),
+                });
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Reachable tiles as CSV, with a `row,col,distance` header
+    pub fn to_csv(&self) -> String {
+        let mut csv = "row,col,distance\n".to_string();
+        for (row, col, distance) in self.reachable() {
+            let _ = writeln!(csv, "{row},{col},{distance}");
+        }
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distances() -> miette::Result<()> {
+        let maze = PipeMaze::parse(
+            "7-F7-
+.FJ|7
+SJLL7
+|F--J
+LJ.LJ",
+        )?;
+        let distances = DistanceMap::new(&maze);
+        assert_eq!(Some((2, 4, 8)), distances.farthest());
+        assert_eq!(8, maze.walk()?.farthest());
+        assert_eq!(Some(3), distances.get(1, 2));
+        assert_eq!(None, distances.get(0, 0));
+        assert_eq!(
+            "7-45-
+.2367
+01L78
+14567
+23.LJ
+",
+            distances.to_text(&maze)
+        );
+        assert_eq!(
+            Some("row,col,distance\n0,2,4"),
+            distances.to_csv().get(..22)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,179 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::maze::{opposite, tile, EAST, NORTH, SOUTH, WEST};
+
+/// A random input with the answers it was built to have
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    pub input: String,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+/// The 8 cells around a cell, clockwise from north
+const RING: [(isize, isize); 8] = [
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+];
+
+/// A maze whose loop encloses a random blob of `size` (at least one) 2 by 2 blocks of tiles, in
+/// junk pipes. The blob grows a block at a time, never making a hole or touching itself
+/// corner to corner, so its outline is a single loop through tiles at even rows and columns.
+pub fn generate(seed: u64, size: usize) -> Puzzle {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let size = size.max(1);
+    let blocks = 2 * (size as f64).sqrt().ceil() as usize + 2;
+
+    let mut blob = vec![vec![false; blocks]; blocks];
+    let mut cells = vec![(blocks / 2, blocks / 2)];
+    blob[blocks / 2][blocks / 2] = true;
+    let mut attempts = 0;
+    while cells.len() < size && attempts < 100 * size {
+        attempts += 1;
+        let (row, col) = cells[rng.gen_range(0..cells.len())];
+        let (dr, dc) = RING[2 * rng.gen_range(0..4)];
+        let (row, col) = (row as isize + dr, col as isize + dc);
+        if can_grow(&blob, row, col) {
+            blob[row as usize][col as usize] = true;
+            cells.push((row as usize, col as usize));
+        }
+    }
+
+    // Outline every block side facing out of the blob.
+    let side = 2 * blocks + 1;
+    let mut sides = vec![vec![0u8; side]; side];
+    let is_blob = |row, col| in_blob(&blob, row, col);
+    for &(row, col) in &cells {
+        let (r, c) = (2 * row, 2 * col);
+        let (br, bc) = (row as isize, col as isize);
+        if !is_blob(br - 1, bc) {
+            horizontal(&mut sides, r, c);
+        }
+        if !is_blob(br + 1, bc) {
+            horizontal(&mut sides, r + 2, c);
+        }
+        if !is_blob(br, bc - 1) {
+            vertical(&mut sides, r, c);
+        }
+        if !is_blob(br, bc + 1) {
+            vertical(&mut sides, r, c + 2);
+        }
+    }
+    let pipes = sides.iter().flatten().filter(|&&s| s != 0).count();
+
+    // S somewhere on the loop, no junk pipe around it may lead into it.
+    let on_loop = (0..side * side)
+        .filter(|i| sides[i / side][i % side] != 0)
+        .collect::<Vec<_>>();
+    let start = on_loop[rng.gen_range(0..on_loop.len())];
+    let start = (start / side, start % side);
+    let mut forbidden = vec![vec![0u8; side]; side];
+    for (dir, (dr, dc)) in [
+        (NORTH, (-1, 0)),
+        (EAST, (0, 1)),
+        (SOUTH, (1, 0)),
+        (WEST, (0, -1)),
+    ] {
+        let (row, col) = (start.0 as isize + dr, start.1 as isize + dc);
+        if (0..side as isize).contains(&row) && (0..side as isize).contains(&col) {
+            forbidden[row as usize][col as usize] = opposite(dir);
+        }
+    }
+    let mut grid = vec![vec!['.'; side]; side];
+    for (row, line) in grid.iter_mut().enumerate() {
+        for (col, t) in line.iter_mut().enumerate() {
+            *t = match sides[row][col] {
+                _ if (row, col) == start => 'S',
+                0 => junk(&mut rng, forbidden[row][col]),
+                s => tile(s).expect("outline tiles connect two sides"),
+            };
+        }
+    }
+
+    let mut input = String::new();
+    for line in grid {
+        input.extend(line);
+        input.push('\n');
+    }
+    Puzzle {
+        input,
+        part1: Some((pipes / 2).to_string()),
+        part2: None,
+    }
+}
+
+/// Whether adding the block at (`row`, `col`) keeps the blob one piece without holes or
+/// corner-only contacts: its blob neighbours make a single run around it, and a corner in the
+/// blob has a side next to it in the blob too.
+fn can_grow(blob: &[Vec<bool>], row: isize, col: isize) -> bool {
+    let inside = (0..blob.len() as isize).contains(&row) && (0..blob.len() as isize).contains(&col);
+    if !inside || in_blob(blob, row, col) {
+        return false;
+    }
+    let ring = RING.map(|(dr, dc)| in_blob(blob, row + dr, col + dc));
+    let runs = (0..8).filter(|&i| ring[i] && !ring[(i + 7) % 8]).count();
+    let pinched = (1..8)
+        .step_by(2)
+        .any(|i| ring[i] && !ring[i - 1] && !ring[(i + 1) % 8]);
+    runs == 1 && !pinched
+}
+
+/// Whether the block at (`row`, `col`) is in the blob, nothing outside the grid is
+fn in_blob(blob: &[Vec<bool>], row: isize, col: isize) -> bool {
+    row >= 0 && col >= 0 && blob.get(row as usize).and_then(|r| r.get(col as usize)) == Some(&true)
+}
+
+/// Outline from (`row`, `col`) two tiles east
+fn horizontal(sides: &mut [Vec<u8>], row: usize, col: usize) {
+    sides[row][col] |= EAST;
+    sides[row][col + 1] |= EAST | WEST;
+    sides[row][col + 2] |= WEST;
+}
+
+/// Outline from (`row`, `col`) two tiles south
+fn vertical(sides: &mut [Vec<u8>], row: usize, col: usize) {
+    sides[row][col] |= SOUTH;
+    sides[row + 1][col] |= NORTH | SOUTH;
+    sides[row + 2][col] |= NORTH;
+}
+
+/// A random pipe or ground not connecting side `forbidden`
+fn junk(rng: &mut StdRng, forbidden: u8) -> char {
+    loop {
+        let sides = match rng.gen_range(0..8) {
+            0 => NORTH | SOUTH,
+            1 => EAST | WEST,
+            2 => NORTH | EAST,
+            3 => NORTH | WEST,
+            4 => SOUTH | WEST,
+            5 => EAST | SOUTH,
+            _ => 0,
+        };
+        if sides & forbidden == 0 {
+            return tile(sides).expect("junk is a tile");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate() -> miette::Result<()> {
+        for seed in 0..20 {
+            let puzzle = generate(seed, 60);
+            assert_eq!(puzzle.part1.unwrap(), crate::part1::process(&puzzle.input)?);
+        }
+        // A single block: a 3 by 3 loop around one tile
+        let puzzle = generate(0, 1);
+        assert_eq!(Some("4".to_string()), puzzle.part1);
+        Ok(())
+    }
+}
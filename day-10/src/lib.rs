@@ -1,4 +1,7 @@
 pub mod custom_error;
 
+pub mod distance;
+pub mod gen;
+pub mod maze;
 pub mod part1;
 pub mod part2;
@@ -0,0 +1,254 @@
+use crate::{custom_error::AocError, distance::DistanceMap};
+
+/// Connection bits of a tile, one per side
+pub const NORTH: u8 = 0b0001;
+pub const EAST: u8 = 0b0010;
+pub const SOUTH: u8 = 0b0100;
+pub const WEST: u8 = 0b1000;
+
+/// Tiles and the sides they connect, ground connects nothing.
+const TILES: [(char, u8); 7] = [
+    ('|', NORTH | SOUTH),
+    ('-', EAST | WEST),
+    ('L', NORTH | EAST),
+    ('J', NORTH | WEST),
+    ('7', SOUTH | WEST),
+    ('F', EAST | SOUTH),
+    ('.', 0),
+];
+
+/// The side facing `side` on the neighbouring tile
+pub fn opposite(side: u8) -> u8 {
+    match side {
+        NORTH => SOUTH,
+        EAST => WEST,
+        SOUTH => NORTH,
+        _ => EAST,
+    }
+}
+
+/// The tile connecting exactly `sides`
+pub fn tile(sides: u8) -> Option<char> {
+    TILES.iter().find(|(_, s)| *s == sides).map(|(t, _)| *t)
+}
+
+/// The loop found from `S`, tiles as `(row, col)` starting with `S` itself
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PipeLoop {
+    pub path: Vec<(usize, usize)>,
+    pub start_tile: char,
+}
+
+impl PipeLoop {
+    /// Steps to the tile farthest from `S` along the loop
+    pub fn farthest(&self) -> usize {
+        self.path.len() / 2
+    }
+}
+
+/// The maze as a grid of connection bitmasks, `S` replaced by the shape it must have.
+#[derive(Debug)]
+pub struct PipeMaze {
+    tiles: Vec<u8>,
+    width: usize,
+    height: usize,
+    start: (usize, usize),
+}
+
+impl PipeMaze {
+    /// Parse the input, all lines must be of equal length and hold exactly one `S`.
+    pub fn parse(input: &str) -> Result<Self, AocError> {
+        let width = input.lines().next().map_or(0, str::len);
+        let mut tiles = Vec::with_capacity(input.len());
+        let mut start = None;
+        let mut height = 0;
+
+        for (row, line) in input.lines().enumerate() {
+            if line.len() != width {
+                return Err(AocError::RaggedMaze(row + 1, line.len(), width));
+            }
+            for (col, c) in line.chars().enumerate() {
+                tiles.push(match c {
+                    'S' if start.is_some() => return Err(AocError::ManyStarts),
+                    'S' => {
+                        start = Some((row, col));
+                        0 // Inferred below
+                    }
+                    c => TILES
+                        .iter()
+                        .find(|(t, _)| *t == c)
+                        .map(|(_, sides)| *sides)
+                        .ok_or(AocError::BadTile(c, row + 1, col + 1))?,
+                });
+            }
+            height += 1;
+        }
+
+        let mut maze = Self {
+            tiles,
+            width,
+            height,
+            start: start.ok_or(AocError::NoStart)?,
+        };
+
+        // S connects to two of the neighbours connecting back. Junk pipes may point at S as
+        // well, then keep the first pair of sides closing a loop.
+        let (row, col) = maze.start;
+        let candidates = [NORTH, EAST, SOUTH, WEST]
+            .into_iter()
+            .filter(|side| {
+                maze.step(row, col, *side)
+                    .is_some_and(|(r, c)| maze.connections(r, c) & opposite(*side) != 0)
+            })
+            .collect::<Vec<_>>();
+        if let [first, second] = candidates[..] {
+            maze.tiles[row * width + col] = first | second;
+            return Ok(maze);
+        }
+        let pairs = candidates
+            .iter()
+            .enumerate()
+            .flat_map(|(i, first)| candidates[i + 1..].iter().map(move |second| first | second));
+        for sides in pairs {
+            maze.tiles[row * width + col] = sides;
+            if maze.walk().is_ok() {
+                return Ok(maze);
+            }
+        }
+        Err(AocError::AmbiguousStart(candidates.len()))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Position of `S` as `(row, col)`
+    pub fn start(&self) -> (usize, usize) {
+        self.start
+    }
+
+    /// Sides the tile at (row, col) connects, none outside the grid
+    pub fn connections(&self, row: usize, col: usize) -> u8 {
+        if row < self.height && col < self.width {
+            self.tiles[row * self.width + col]
+        } else {
+            0
+        }
+    }
+
+    /// The tile at (row, col) drawn as in the input, `S` drawn as its inferred shape
+    pub fn tile_at(&self, row: usize, col: usize) -> char {
+        tile(self.connections(row, col)).unwrap_or('?')
+    }
+
+    /// The neighbour of (row, col) on `side`, `None` outside the grid
+    pub fn step(&self, row: usize, col: usize, side: u8) -> Option<(usize, usize)> {
+        let (row, col) = match side {
+            NORTH => (row.checked_sub(1)?, col),
+            EAST => (row, col + 1),
+            SOUTH => (row + 1, col),
+            _ => (row, col.checked_sub(1)?),
+        };
+        (row < self.height && col < self.width).then_some((row, col))
+    }
+
+    /// Steps from `S` to every tile connected to it
+    pub fn distances(&self) -> DistanceMap {
+        DistanceMap::new(self)
+    }
+
+    /// Walk the loop from `S`, leaving every tile by the side we didn't come in by.
+    pub fn walk(&self) -> Result<PipeLoop, AocError> {
+        let (mut row, mut col) = self.start;
+        let start_sides = self.connections(row, col);
+        let mut side = start_sides & start_sides.wrapping_neg(); // Lowest bit set
+        let mut path = vec![self.start];
+
+        loop {
+            (row, col) = self
+                .step(row, col, side)
+                .ok_or(AocError::BrokenLoop(row, col))?;
+            let sides = self.connections(row, col);
+            if sides & opposite(side) == 0 {
+                return Err(AocError::BrokenLoop(row, col));
+            }
+            if (row, col) == self.start {
+                break;
+            }
+            path.push((row, col));
+            side = sides & !opposite(side);
+        }
+
+        Ok(PipeLoop {
+            path,
+            start_tile: tile(start_sides).unwrap_or('S'),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_walk() -> miette::Result<()> {
+        let maze = PipeMaze::parse(
+            "-L|F7
+7S-7|
+L|7||
+-L-J|
+L|-JF",
+        )?;
+        assert_eq!((1, 1), maze.start());
+        assert_eq!(EAST | SOUTH, maze.connections(1, 1));
+
+        let pipe_loop = maze.walk()?;
+        assert_eq!('F', pipe_loop.start_tile);
+        assert_eq!(
+            vec![
+                (1, 1),
+                (1, 2),
+                (1, 3),
+                (2, 3),
+                (3, 3),
+                (3, 2),
+                (3, 1),
+                (2, 1)
+            ],
+            pipe_loop.path
+        );
+        assert_eq!(4, pipe_loop.farthest());
+        Ok(())
+    }
+
+    #[test]
+    fn test_bad_mazes() {
+        assert!(PipeMaze::parse("S-\n.").is_err());
+        assert!(PipeMaze::parse("S-S").is_err());
+        assert!(PipeMaze::parse("F-7\n|.|\nL-J").is_err());
+        assert!(PipeMaze::parse("F-7\n|x|\nS-J").is_err());
+        // S could connect three ways, none closing a loop.
+        assert!(PipeMaze::parse(".|.\n-S-\n...").is_err());
+        // S connects to a single pipe.
+        assert!(PipeMaze::parse("S-.\n...").is_err());
+    }
+
+    #[test]
+    fn test_junk_pointing_at_start() -> miette::Result<()> {
+        // The junk pipe north of S connects back to it, but only east and south close a loop.
+        let maze = PipeMaze::parse(
+            ".|...
+.S-7.
+.|.|.
+.L-J.
+.....",
+        )?;
+        assert_eq!(EAST | SOUTH, maze.connections(1, 1));
+        assert_eq!('F', maze.walk()?.start_tile);
+        Ok(())
+    }
+}
@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Any text may be wrong, but never make a part panic.
+fuzz_target!(|input: &str| {
+    let _ = day_02::part1::process(input);
+    let _ = day_02::part2::process(input);
+});
@@ -0,0 +1,236 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fmt::Write as _,
+    hint::black_box,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering::Relaxed},
+        Mutex, PoisonError,
+    },
+};
+
+use serde::Serialize;
+
+use crate::{custom_error::AocError, days::Part, history::Allocations};
+
+/// The system allocator, counting while `measure` runs. Install it in a binary with
+///
+/// ```text
+///     #[global_allocator]
+///     static ALLOC: Counting = Counting;
+/// ```
+///
+/// Outside of `measure` it costs a flag check per call, so it can stay installed for commands
+/// that time or profile. Counts are process wide, so calls of `measure` take turns.
+pub struct Counting;
+
+static TURN: Mutex<()> = Mutex::new(()); // Held by the running `measure`
+static MEASURING: AtomicBool = AtomicBool::new(false);
+static TOTAL_BYTES: AtomicU64 = AtomicU64::new(0);
+static TOTAL_BLOCKS: AtomicU64 = AtomicU64::new(0);
+// Only counted while measuring, so live counts drift and can drop below 0.
+static LIVE_BYTES: AtomicI64 = AtomicI64::new(0);
+static LIVE_BLOCKS: AtomicI64 = AtomicI64::new(0);
+static PEAK_BYTES: AtomicI64 = AtomicI64::new(0);
+static PEAK_BLOCKS: AtomicI64 = AtomicI64::new(0); // Live at the byte peak
+
+fn grown(bytes: i64, blocks: i64) {
+    let live = LIVE_BYTES.fetch_add(bytes, Relaxed) + bytes;
+    let live_blocks = LIVE_BLOCKS.fetch_add(blocks, Relaxed) + blocks;
+    if PEAK_BYTES.fetch_max(live, Relaxed) < live {
+        PEAK_BLOCKS.store(live_blocks, Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if MEASURING.load(Relaxed) && !ptr.is_null() {
+            TOTAL_BYTES.fetch_add(layout.size() as u64, Relaxed);
+            TOTAL_BLOCKS.fetch_add(1, Relaxed);
+            grown(layout.size() as i64, 1);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        if MEASURING.load(Relaxed) {
+            LIVE_BYTES.fetch_sub(layout.size() as i64, Relaxed);
+            LIVE_BLOCKS.fetch_sub(1, Relaxed);
+        }
+    }
+
+    /// A reallocation counts as a new block of the new size, like dhat does.
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if MEASURING.load(Relaxed) && !new_ptr.is_null() {
+            TOTAL_BYTES.fetch_add(new_size as u64, Relaxed);
+            TOTAL_BLOCKS.fetch_add(1, Relaxed);
+            grown(new_size as i64 - layout.size() as i64, 0);
+        }
+        new_ptr
+    }
+}
+
+/// Whether `Counting` is the global allocator
+pub fn installed() -> bool {
+    let (_, allocations) = measure(|| black_box(Box::new(0u8)));
+    allocations.total_blocks > 0
+}
+
+/// Allocations made by `f`, peaks relative to what was live before. Waits for other calls to
+/// finish, so `f` can't call `measure` itself.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Allocations) {
+    // Resetting the peaks while another call runs would lose its peak.
+    let _turn = TURN.lock().unwrap_or_else(PoisonError::into_inner);
+    let (live_bytes, live_blocks) = (LIVE_BYTES.load(Relaxed), LIVE_BLOCKS.load(Relaxed));
+    let (total_bytes, total_blocks) = (TOTAL_BYTES.load(Relaxed), TOTAL_BLOCKS.load(Relaxed));
+    PEAK_BYTES.store(live_bytes, Relaxed);
+    PEAK_BLOCKS.store(live_blocks, Relaxed);
+
+    MEASURING.store(true, Relaxed);
+    let result = f();
+    MEASURING.store(false, Relaxed);
+
+    let allocations = Allocations {
+        total_bytes: TOTAL_BYTES.load(Relaxed) - total_bytes,
+        total_blocks: TOTAL_BLOCKS.load(Relaxed) - total_blocks,
+        peak_bytes: (PEAK_BYTES.load(Relaxed) - live_bytes).max(0) as u64,
+        peak_blocks: (PEAK_BLOCKS.load(Relaxed) - live_blocks).max(0) as u64,
+    };
+    (result, allocations)
+}
+
+/// Allocations of one part, parsing apart from the rest
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Report {
+    pub day: u8,
+    pub part: u8,
+    /// Parsing alone, for days with a parser of their own
+    pub parse: Option<Allocations>,
+    /// `process` minus parsing. Its peak can't be told apart from parsing's, see `process`.
+    pub solve: Totals,
+    /// The whole of `process`
+    pub process: Allocations,
+}
+
+/// Allocations without a peak
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Totals {
+    pub total_bytes: u64,
+    pub total_blocks: u64,
+}
+
+impl From<Allocations> for Totals {
+    fn from(allocations: Allocations) -> Self {
+        Self {
+            total_bytes: allocations.total_bytes,
+            total_blocks: allocations.total_blocks,
+        }
+    }
+}
+
+/// Run `part` once parsing only and once whole, counting allocations.
+pub fn report(part: &Part) -> miette::Result<Report> {
+    if !installed() {
+        return Err(AocError::NotCounting.into());
+    }
+    let parse = part.parse.map(|parse| measure(|| parse(part.input)).1);
+    let (answer, process) = measure(|| (part.process)(part.input));
+    answer?;
+
+    let before = parse.unwrap_or_default();
+    let solve = Totals {
+        total_bytes: process.total_bytes.saturating_sub(before.total_bytes),
+        total_blocks: process.total_blocks.saturating_sub(before.total_blocks),
+    };
+    Ok(Report {
+        day: part.day,
+        part: part.part,
+        parse,
+        solve,
+        process,
+    })
+}
+
+/// The reports as a table, parse, solve and the whole process on a line each. Solving has no
+/// peak of its own, the process line holds the peak over both.
+pub fn table(reports: &[Report]) -> String {
+    let mut table = format!(
+        "{:<13} {:<7} {:>14} {:>10} {:>14} {:>10}\n",
+        "part", "stage", "total bytes", "blocks", "peak bytes", "blocks"
+    );
+    for report in reports {
+        let stages = [
+            ("parse", report.parse.map(|a| (a.into(), Some(a)))),
+            ("solve", Some((report.solve, None))),
+            (
+                "process",
+                Some((report.process.into(), Some(report.process))),
+            ),
+        ];
+        for (stage, row) in stages {
+            let Some((totals, peak)) = row else { continue };
+            let peak = peak.map_or(String::new(), |a: Allocations| {
+                format!("{:>14} {:>10}", a.peak_bytes, a.peak_blocks)
+            });
+            let line = format!(
+                "day-{:02} part{} {:<7} {:>14} {:>10} {peak}",
+                report.day, report.part, stage, totals.total_bytes, totals.total_blocks
+            );
+            let _ = writeln!(table, "{}", line.trim_end());
+        }
+    }
+    table
+}
+
+/// Write the reports of every day to `dir/day-NN.json`.
+pub fn save(dir: &Path, reports: &[Report]) -> Result<(), AocError> {
+    std::fs::create_dir_all(dir)?;
+    for chunk in reports.chunk_by(|a, b| a.day == b.day) {
+        let path = dir.join(format!("day-{:02}.json", chunk[0].day));
+        std::fs::write(path, serde_json::to_string_pretty(chunk)? + "\n")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOC: Counting = Counting;
+
+    #[test]
+    fn test_measure() {
+        // Other tests allocate too, so only lower bounds hold.
+        let (v, allocations) = measure(|| {
+            let mut v = Vec::<u8>::with_capacity(1000);
+            v.extend_from_slice(&[1; 1000]);
+            v.shrink_to(0); // Drops the block
+            vec![0u64; 500]
+        });
+        assert!(installed());
+        assert_eq!(500, v.len());
+        assert!(allocations.total_bytes >= 5000);
+        assert!(allocations.total_blocks >= 2);
+        assert!(allocations.peak_bytes >= 4000);
+    }
+
+    #[test]
+    fn test_report() -> miette::Result<()> {
+        let part = crate::days::parts()
+            .into_iter()
+            .find(|p| p.day == 3)
+            .unwrap();
+        let report = report(&part)?;
+        let parse = report.parse.unwrap();
+        assert!(parse.total_blocks > 0);
+        assert!(report.process.total_bytes >= parse.total_bytes);
+        let table = table(&[report]);
+        assert!(table.contains("day-03 part1 parse"));
+        assert!(table.contains("day-03 part1 process"));
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+use miette::Diagnostic;
+use thiserror::Error;
+
+#[derive(Error, Diagnostic, Debug)]
+pub enum AocError {
+    #[error(transparent)]
+    #[diagnostic(code(aoc::io_error))]
+    IoError(#[from] std::io::Error),
+    #[error("History line {0} is not a record: {1}")]
+    #[diagnostic(code(aoc::bad_history))]
+    BadHistory(usize, serde_json::Error),
+    #[error("`{0}` failed:\n{1}")]
+    #[diagnostic(code(aoc::command_failed))]
+    CommandFailed(String, String),
+    #[error("No baseline at {0}")]
+    #[diagnostic(code(aoc::no_baseline), help("record one with `runner gate --update`"))]
+    NoBaseline(String),
+    #[error("No day under {0} has divan benches")]
+    #[diagnostic(code(aoc::no_benches))]
+    NoBenches(String),
+    #[error("The counting allocator is not installed")]
+    #[diagnostic(code(aoc::not_counting))]
+    NotCounting,
+    #[error("Profiling failed: {0}")]
+    #[diagnostic(code(aoc::profiler_error))]
+    ProfilerError(String),
+    #[error("No solutions for day {0}")]
+    #[diagnostic(code(aoc::unknown_day))]
+    UnknownDay(u8),
+    #[error("{0} answered {1} on generated size {2}, expected {3}")]
+    #[diagnostic(code(aoc::wrong_answer))]
+    WrongAnswer(String, String, usize, String),
+    #[error(transparent)]
+    #[diagnostic(code(aoc::thread_pool_error))]
+    ThreadPoolError(#[from] rayon::ThreadPoolBuildError),
+    #[error(transparent)]
+    #[diagnostic(code(aoc::json_error))]
+    JsonError(#[from] serde_json::Error),
+}
@@ -0,0 +1,92 @@
+//! Every implemented day and part, with its puzzle input
+
+/// A solver for one part of a day
+pub struct Part {
+    pub day: u8,
+    pub part: u8,
+    pub input: &'static str,
+    /// Parse the input and throw the result away, for days with a parser of their own
+    pub parse: Option<fn(&str) -> miette::Result<()>>,
+    pub process: fn(&str) -> miette::Result<String>,
+    /// A generated input from a seed and a size, with this part's answer if known
+    pub generate: fn(u64, usize) -> (String, Option<String>),
+    /// Largest size the generator takes, inputs stop growing past it
+    pub max_size: usize,
+}
+
+impl Part {
+    /// Like `day-03 part2`
+    pub fn name(&self) -> String {
+        format!("day-{:02} part{}", self.day, self.part)
+    }
+}
+
+/// Both parts of a day, with the parser to time on its own if given, and after a `;` the
+/// largest size its generator takes if it has one.
+macro_rules! day {
+    ($day:literal, $krate:ident, $dir:literal $(, $parse:path)?) => {
+        day!(@parts $day, $krate, $dir, day!(@parse $($parse)?), usize::MAX)
+    };
+    ($day:literal, $krate:ident, $dir:literal $(, $parse:path)?; max_size = $max:expr) => {
+        day!(@parts $day, $krate, $dir, day!(@parse $($parse)?), $max)
+    };
+    (@parse) => {
+        None
+    };
+    (@parse $parse:path) => {
+        Some(|input| {
+            $parse(input)?;
+            Ok(())
+        })
+    };
+    (@parts $day:literal, $krate:ident, $dir:literal, $parse:expr, $max:expr) => {
+        [
+            Part {
+                day: $day,
+                part: 1,
+                input: include_str!(concat!("../../", $dir, "/input1.txt")),
+                parse: $parse,
+                process: |input| Ok($krate::part1::process(input)?),
+                generate: |seed, size| {
+                    let puzzle = $krate::gen::generate(seed, size);
+                    (puzzle.input, puzzle.part1)
+                },
+                max_size: $max,
+            },
+            Part {
+                day: $day,
+                part: 2,
+                input: include_str!(concat!("../../", $dir, "/input2.txt")),
+                parse: $parse,
+                process: |input| Ok($krate::part2::process(input)?),
+                generate: |seed, size| {
+                    let puzzle = $krate::gen::generate(seed, size);
+                    (puzzle.input, puzzle.part2)
+                },
+                max_size: $max,
+            },
+        ]
+    };
+}
+
+/// Parts whose solver is still a `todo!` stub
+pub const UNSOLVED: [(u8, u8); 1] = [(10, 2)];
+
+/// All solved parts in order
+pub fn parts() -> Vec<Part> {
+    [
+        day!(1, day_01, "day-01"),
+        day!(2, day_02, "day-02", day_02::game::parse_games),
+        day!(3, day_03, "day-03", day_03::schematic::Schematic::parse),
+        day!(4, day_04, "day-04", day_04::card::parse_cards),
+        day!(5, day_05, "day-05", day_05::almanac::Almanac::parse),
+        day!(7, day_07, "day-07"; max_size = day_07::gen::MAX_SIZE),
+        day!(8, day_08, "day-08", day_08::network::Network::parse; max_size = day_08::gen::MAX_SIZE),
+        day!(9, day_09, "day-09", day_09::engine::check),
+        day!(10, day_10, "day-10", day_10::maze::PipeMaze::parse),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|part| !UNSOLVED.contains(&(part.day, part.part)))
+    .collect()
+}
@@ -0,0 +1,225 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    hint::black_box,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    custom_error::AocError,
+    days::Part,
+    history::{format_ns, Record},
+};
+
+/// Where `runner flamegraph` writes, relative to the workspace root
+pub const FLAMEGRAPHS: &str = "flamegraphs";
+
+/// Samples of one part run over and over
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    pub day: u8,
+    pub part: u8,
+    pub iterations: u64,
+    pub samples: u64,
+    /// Stacks in the folded format, root first, like `day-03 part1;main;process 12`
+    pub folded: Vec<String>,
+}
+
+impl Profile {
+    /// File name without extension, like the justfile's `day-03--part1`
+    pub fn stem(&self) -> String {
+        format!("day-{:02}--part{}", self.day, self.part)
+    }
+}
+
+/// Run `part` for `duration` (at least once) under pprof sampling `frequency` times a second.
+pub fn profile(part: &Part, duration: Duration, frequency: i32) -> miette::Result<Profile> {
+    let guard = pprof::ProfilerGuardBuilder::default()
+        .frequency(frequency)
+        .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+        .build()
+        .map_err(|e| AocError::ProfilerError(e.to_string()))?;
+
+    let start = Instant::now();
+    let mut iterations = 0;
+    while iterations == 0 || start.elapsed() < duration {
+        black_box((part.process)(black_box(part.input))?);
+        iterations += 1;
+    }
+
+    let report = guard
+        .report()
+        .build()
+        .map_err(|e| AocError::ProfilerError(e.to_string()))?;
+    let root = part.name();
+    let mut samples = 0;
+    let mut folded = report
+        .data
+        .iter()
+        .map(|(frames, count)| {
+            samples += *count as u64;
+            let symbols = frames
+                .frames
+                .iter()
+                .rev()
+                .flat_map(|frame| frame.iter().rev())
+                .map(|symbol| symbol.to_string())
+                .collect::<Vec<_>>();
+            // The runner's own frames are the same on every stack, start at the solver.
+            let own = symbols
+                .iter()
+                .rposition(|symbol| symbol.starts_with("runner::flame::profile"))
+                .map_or(0, |i| i + 1);
+            let mut line = root.clone();
+            for symbol in &symbols[own..] {
+                let _ = write!(line, ";{symbol}");
+            }
+            format!("{line} {count}")
+        })
+        .collect::<Vec<_>>();
+    folded.sort();
+
+    Ok(Profile {
+        day: part.day,
+        part: part.part,
+        iterations,
+        samples,
+        folded,
+    })
+}
+
+/// The flamegraph of `profile` as SVG
+pub fn svg(profile: &Profile) -> Result<Vec<u8>, AocError> {
+    let mut options = inferno::flamegraph::Options::default();
+    options.title = format!("day-{:02} part{}", profile.day, profile.part);
+    options.subtitle = Some(format!(
+        "{} samples over {} runs",
+        profile.samples, profile.iterations
+    ));
+    let mut svg = vec![];
+    inferno::flamegraph::from_lines(
+        &mut options,
+        profile.folded.iter().map(String::as_str),
+        &mut svg,
+    )
+    .map_err(|e| AocError::ProfilerError(e.to_string()))?;
+    Ok(svg)
+}
+
+/// Write `<stem>.folded` and `<stem>.svg` for every profile and an `index.html` linking them,
+/// with the latest median time and peak heap of each part found in `records`.
+pub fn save(dir: &Path, profiles: &[Profile], records: &[Record]) -> Result<(), AocError> {
+    std::fs::create_dir_all(dir)?;
+    for profile in profiles {
+        let stem = profile.stem();
+        std::fs::write(
+            dir.join(format!("{stem}.folded")),
+            profile.folded.join("\n") + "\n",
+        )?;
+        if profile.folded.is_empty() {
+            tracing::warn!(stem, "no samples, no flamegraph");
+        } else {
+            std::fs::write(dir.join(format!("{stem}.svg")), svg(profile)?)?;
+        }
+    }
+    std::fs::write(dir.join("index.html"), index(profiles, records))?;
+    Ok(())
+}
+
+/// An HTML page with a row per profile
+pub fn index(profiles: &[Profile], records: &[Record]) -> String {
+    // Latest median and peak by day and bench, the two may come from different records.
+    let mut latest = BTreeMap::<(u8, &str), (Option<(u64, f64)>, Option<(u64, u64)>)>::new();
+    for record in records {
+        let (timing, heap) = latest.entry((record.day, &record.bench)).or_default();
+        if let Some(t) = record
+            .timing
+            .filter(|_| timing.is_none_or(|(at, _)| at <= record.timestamp))
+        {
+            *timing = Some((record.timestamp, t.median_ns));
+        }
+        if let Some(a) = record
+            .allocations
+            .filter(|_| heap.is_none_or(|(at, _)| at <= record.timestamp))
+        {
+            *heap = Some((record.timestamp, a.peak_bytes));
+        }
+    }
+
+    let mut html = String::from(
+        "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>Flamegraphs</title>
+<style>
+  body { font-family: sans-serif; }
+  td, th { padding: 0.2em 1em; text-align: right; }
+</style>
+</head>
+<body>
+<h1>Flamegraphs</h1>
+<table>
+<tr><th>part</th><th>runs</th><th>samples</th><th>median</th><th>peak heap</th><th>stacks</th></tr>
+",
+    );
+    for profile in profiles {
+        let stem = profile.stem();
+        let bench = format!("part{}", profile.part);
+        let (timing, heap) = latest
+            .get(&(profile.day, bench.as_str()))
+            .copied()
+            .unwrap_or_default();
+        let name = if profile.folded.is_empty() {
+            format!("day-{:02} part{}", profile.day, profile.part)
+        } else {
+            format!(
+                "<a href=\"{stem}.svg\">day-{:02} part{}</a>",
+                profile.day, profile.part
+            )
+        };
+        let _ = writeln!(
+            html,
+            "<tr><td>{name}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td><a href=\"{stem}.folded\">folded</a></td></tr>",
+            profile.iterations,
+            profile.samples,
+            timing.map_or("-".to_string(), |(_, ns)| format_ns(ns)),
+            heap.map_or("-".to_string(), |(_, bytes)| format!("{bytes} B")),
+        );
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::Timing;
+
+    #[test]
+    fn test_index() {
+        let profile = Profile {
+            day: 9,
+            part: 2,
+            iterations: 10,
+            samples: 3,
+            folded: vec!["day-09 part2;main 3".to_string()],
+        };
+        let record = |timestamp, median_ns| Record {
+            day: 9,
+            bench: "part2".to_string(),
+            timing: Some(Timing {
+                fastest_ns: median_ns,
+                median_ns,
+                mean_ns: median_ns,
+            }),
+            allocations: None,
+            timestamp,
+            commit: None,
+        };
+        let html = index(&[profile], &[record(2, 1500.0), record(1, 900.0)]);
+        assert!(html.contains("<a href=\"day-09--part2.svg\">day-09 part2</a>"));
+        assert!(html.contains("<td>1.50 µs</td><td>-</td>"));
+    }
+}
@@ -0,0 +1,335 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Write as _},
+    path::Path,
+    process::Command,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    alloc,
+    custom_error::AocError,
+    days,
+    history::{format_ns, parse_report, Record, Stamp},
+};
+
+/// Where `runner gate` finds its baseline, relative to the workspace root
+pub const BASELINE: &str = "bench-baseline.json";
+
+/// Measurements to compare against, and how much worse they may get
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    /// Relative slack, 0.15 lets a bench get 15% slower or heavier
+    pub tolerance: f64,
+    /// Slack by day, for days noisier than others
+    #[serde(default)]
+    pub tolerances: BTreeMap<u8, f64>,
+    pub records: Vec<Record>,
+}
+
+impl Default for Baseline {
+    fn default() -> Self {
+        Self {
+            tolerance: 0.15,
+            tolerances: BTreeMap::new(),
+            records: vec![],
+        }
+    }
+}
+
+impl Baseline {
+    /// Read the baseline at `path`, which has to exist.
+    pub fn load(path: &Path) -> Result<Self, AocError> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Ok(serde_json::from_str(&text)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Err(AocError::NoBaseline(path.display().to_string()))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Like `load`, but a missing file is an empty baseline, for a first `--update`.
+    pub fn load_or_default(path: &Path) -> Result<Self, AocError> {
+        match Self::load(path) {
+            Err(AocError::NoBaseline(_)) => Ok(Self::default()),
+            baseline => baseline,
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), AocError> {
+        std::fs::write(path, serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+
+    /// Slack for `day`
+    pub fn tolerance(&self, day: u8) -> f64 {
+        self.tolerances.get(&day).copied().unwrap_or(self.tolerance)
+    }
+
+    /// Replace the records of every day measured in `records`, leaving other days be. Timings
+    /// and heap numbers of a bench end up in one record.
+    pub fn update(&mut self, records: &[Record]) {
+        self.records
+            .retain(|old| records.iter().all(|new| new.day != old.day));
+
+        let mut merged = BTreeMap::<(u8, &str), Record>::new();
+        for record in &self.records {
+            merged.insert((record.day, &record.bench), record.clone());
+        }
+        for record in records {
+            let entry = merged
+                .entry((record.day, &record.bench))
+                .or_insert_with(|| record.clone());
+            entry.timing = record.timing.or(entry.timing);
+            entry.allocations = record.allocations.or(entry.allocations);
+        }
+        self.records = merged.into_values().collect();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Metric {
+    Median,
+    PeakBytes,
+}
+
+impl Metric {
+    fn format(&self, value: f64) -> String {
+        match self {
+            Metric::Median => format_ns(value),
+            Metric::PeakBytes => format!("{value} B"),
+        }
+    }
+}
+
+impl Display for Metric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Metric::Median => write!(f, "median"),
+            Metric::PeakBytes => write!(f, "peak heap"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Ok,
+    Improved,
+    Regressed,
+    New, // Nothing to compare with
+}
+
+/// One metric of one bench, now and in the baseline
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub day: u8,
+    pub bench: String,
+    pub metric: Metric,
+    pub baseline: Option<f64>,
+    pub current: f64,
+    pub verdict: Verdict,
+}
+
+/// Compare the median times and peak heap bytes of `current` against the baseline's. When a
+/// bench was measured more than once, the last measurement counts.
+pub fn compare(baseline: &Baseline, current: &[Record]) -> Vec<Comparison> {
+    let metrics = |records: &[Record]| {
+        let mut metrics = BTreeMap::new();
+        for record in records {
+            let key = |metric| (record.day, record.bench.clone(), metric);
+            if let Some(timing) = record.timing {
+                metrics.insert(key(Metric::Median), timing.median_ns);
+            }
+            if let Some(allocations) = record.allocations {
+                metrics.insert(key(Metric::PeakBytes), allocations.peak_bytes as f64);
+            }
+        }
+        metrics
+    };
+    let before = metrics(&baseline.records);
+
+    metrics(current)
+        .into_iter()
+        .map(|((day, bench, metric), current)| {
+            let baseline_value = before.get(&(day, bench.clone(), metric)).copied();
+            let tolerance = baseline.tolerance(day);
+            let verdict = match baseline_value {
+                None => Verdict::New,
+                Some(base) if current > base * (1.0 + tolerance) => Verdict::Regressed,
+                Some(base) if current < base * (1.0 - tolerance) => Verdict::Improved,
+                Some(_) => Verdict::Ok,
+            };
+            Comparison {
+                day,
+                bench,
+                metric,
+                baseline: baseline_value,
+                current,
+                verdict,
+            }
+        })
+        .collect()
+}
+
+/// The comparisons as a table, one line each
+pub fn table(comparisons: &[Comparison]) -> String {
+    let mut table = format!(
+        "{:<6} {:<24} {:<9} {:>12} {:>12} {:>8}  verdict\n",
+        "day", "bench", "metric", "baseline", "current", "change"
+    );
+    for c in comparisons {
+        let (baseline, change) = match c.baseline {
+            Some(base) if base > 0.0 => (
+                c.metric.format(base),
+                format!("{:+.1}%", (c.current / base - 1.0) * 100.0),
+            ),
+            Some(base) => (c.metric.format(base), "".to_string()),
+            None => ("-".to_string(), "".to_string()),
+        };
+        let _ = writeln!(
+            table,
+            "day-{:02} {:<24} {:<9} {:>12} {:>12} {:>8}  {:?}",
+            c.day,
+            c.bench,
+            c.metric.to_string(),
+            baseline,
+            c.metric.format(c.current),
+            change,
+            c.verdict
+        );
+    }
+    table
+}
+
+/// The workspace the runner was built in, wherever it runs from
+pub fn workspace_root() -> &'static Path {
+    Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/.."))
+}
+
+/// Days of the workspace at `root` with divan benches, from their `day-NN` directories
+pub fn benched_days(root: &Path) -> Result<Vec<u8>, AocError> {
+    let mut days = vec![];
+    for entry in std::fs::read_dir(root)? {
+        let path = entry?.path();
+        let day = path
+            .file_name()
+            .and_then(|name| name.to_str()?.strip_prefix("day-")?.parse().ok());
+        if let Some(day) = day.filter(|_| path.join("benches/benchmarks.rs").exists()) {
+            days.push(day);
+        }
+    }
+    days.sort();
+    Ok(days)
+}
+
+/// Run the divan benches of `day`, and with `heap` count what every part allocates in this
+/// process, and collect the measurements.
+pub fn measure(day: u8, heap: bool, stamp: &Stamp) -> miette::Result<Vec<Record>> {
+    let package = format!("day-{day:02}");
+    // Benching a `todo!` would panic.
+    let skips = days::UNSOLVED
+        .iter()
+        .filter(|&&(unsolved, _)| unsolved == day)
+        .flat_map(|(_, part)| ["--skip".to_string(), format!("part{part}")]);
+    let output = Command::new("cargo")
+        .current_dir(workspace_root())
+        .args(["bench", "-q", "-p", &package, "--bench", &package, "--"])
+        .args(skips)
+        .output()
+        .map_err(AocError::from)?;
+    if !output.status.success() {
+        return Err(AocError::CommandFailed(
+            format!("cargo bench -p {package}"),
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+        )
+        .into());
+    }
+    let mut records = parse_report(&String::from_utf8_lossy(&output.stdout), stamp);
+
+    for part in days::parts().iter().filter(|part| heap && part.day == day) {
+        let report = alloc::report(part)?;
+        records.push(Record {
+            day,
+            bench: format!("part{}", part.part),
+            timing: None,
+            allocations: Some(report.process),
+            timestamp: stamp.timestamp,
+            commit: stamp.commit.clone(),
+        });
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::{Allocations, Timing};
+
+    fn record(day: u8, bench: &str, median_ns: f64, peak_bytes: Option<u64>) -> Record {
+        Record {
+            day,
+            bench: bench.to_string(),
+            timing: Some(Timing {
+                fastest_ns: median_ns,
+                median_ns,
+                mean_ns: median_ns,
+            }),
+            allocations: peak_bytes.map(|peak_bytes| Allocations {
+                peak_bytes,
+                ..Default::default()
+            }),
+            timestamp: 0,
+            commit: None,
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        let baseline = Baseline {
+            tolerances: BTreeMap::from([(3, 0.5)]),
+            records: vec![
+                record(1, "part1", 100.0, Some(1024)),
+                record(3, "part1", 1000.0, None),
+            ],
+            ..Default::default()
+        };
+        let current = [
+            record(1, "part1", 120.0, Some(512)),
+            record(3, "part1", 1400.0, None),
+            record(3, "part2", 10.0, None),
+        ];
+
+        let verdicts = compare(&baseline, &current)
+            .into_iter()
+            .map(|c| (c.day, c.bench, c.metric, c.verdict))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (1, "part1".to_string(), Metric::Median, Verdict::Regressed),
+                (1, "part1".to_string(), Metric::PeakBytes, Verdict::Improved),
+                (3, "part1".to_string(), Metric::Median, Verdict::Ok),
+                (3, "part2".to_string(), Metric::Median, Verdict::New),
+            ],
+            verdicts
+        );
+    }
+
+    #[test]
+    fn test_load_missing() {
+        let path = Path::new("no-such-baseline.json");
+        assert!(matches!(Baseline::load(path), Err(AocError::NoBaseline(_))));
+        assert_eq!(
+            Baseline::default(),
+            Baseline::load_or_default(path).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_benched_days() -> Result<(), AocError> {
+        let days = benched_days(workspace_root())?;
+        assert!(days.contains(&1) && days.contains(&10));
+        Ok(())
+    }
+}
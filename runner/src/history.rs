@@ -0,0 +1,427 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::Path,
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{digit1, space0, space1},
+    combinator::{all_consuming, map_res, recognize},
+    multi::separated_list1,
+    number::complete::double,
+    sequence::{pair, preceded, terminated},
+    IResult, Parser,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::custom_error::AocError;
+
+/// Where `runner history` keeps its records, relative to the workspace root
+pub const HISTORY: &str = "bench-history.jsonl";
+
+/// Divan's timings of one bench, in nanoseconds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Timing {
+    pub fastest_ns: f64,
+    pub median_ns: f64,
+    pub mean_ns: f64,
+}
+
+/// Heap summary of one run, from dhat or `alloc::Counting`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Allocations {
+    pub total_bytes: u64,
+    pub total_blocks: u64,
+    pub peak_bytes: u64,
+    pub peak_blocks: u64,
+}
+
+/// One measurement of a day's bench (`part1`, or `group/name` for nested benches), as stored
+/// on one line of the history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    pub day: u8,
+    pub bench: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allocations: Option<Allocations>,
+    pub timestamp: u64, // Seconds since the Unix epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+}
+
+/// When and at which commit measurements were taken
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stamp {
+    pub timestamp: u64,
+    pub commit: Option<String>,
+}
+
+impl Stamp {
+    /// Now, at the commit checked out (if git knows it)
+    pub fn now() -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        let commit = Command::new("git")
+            .args(["rev-parse", "--short", "HEAD"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| String::from_utf8(output.stdout).ok())
+            .map(|commit| commit.trim().to_string());
+        Self { timestamp, commit }
+    }
+}
+
+/// Pick divan tables and dhat summaries out of free-form text like `benchmarks.txt` or the
+/// output of `cargo bench`. Everything else, notes and panics included, is skipped.
+pub fn parse_report(text: &str, stamp: &Stamp) -> Vec<Record> {
+    let mut records = vec![];
+    let mut day = None; // Of the current divan table
+    let mut groups: Vec<String> = vec![]; // Enclosing the current divan row
+    let mut run = None; // Day and bin of the current dhat run
+    let mut total = None; // Index of the current dhat run's total record
+
+    let record = |day, bench: &str| Record {
+        day,
+        bench: bench.to_string(),
+        timing: None,
+        allocations: None,
+        timestamp: stamp.timestamp,
+        commit: stamp.commit.clone(),
+    };
+
+    for line in text.lines() {
+        let line = line.trim_end();
+        if let Ok((_, table_day)) = table_header(line) {
+            day = Some(table_day);
+            groups.clear();
+        } else if let Some((depth, name, columns)) = table_row(line) {
+            let Some(day) = day else { continue };
+            groups.truncate(depth);
+            match timing(columns) {
+                Some(timing) => {
+                    let mut path = groups.clone();
+                    path.push(name.to_string());
+                    records.push(Record {
+                        timing: Some(timing),
+                        ..record(day, &path.join("/"))
+                    });
+                }
+                None if columns.chars().all(|c| c == '│' || c.is_whitespace()) => {
+                    groups.push(name.to_string())
+                }
+                None => tracing::warn!(line, "skipping bench row without timings"),
+            }
+        } else if line.starts_with("cargo run") {
+            run = dhat_run(line);
+            total = None;
+        } else if let Ok((_, (peak, bytes, blocks))) = dhat_summary(line) {
+            let Some((run_day, bin)) = &run else { continue };
+            if !peak {
+                total = Some(records.len());
+                records.push(Record {
+                    allocations: Some(Allocations {
+                        total_bytes: bytes,
+                        total_blocks: blocks,
+                        ..Default::default()
+                    }),
+                    ..record(*run_day, bin)
+                });
+            } else if let Some(allocations) =
+                total.and_then(|total| records[total].allocations.as_mut())
+            {
+                allocations.peak_bytes = bytes;
+                allocations.peak_blocks = blocks;
+            }
+        } else {
+            day = None;
+        }
+    }
+    records
+}
+
+/// Append `records` to the history at `path`, one JSON object per line.
+pub fn append(path: &Path, records: &[Record]) -> Result<(), AocError> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    for record in records {
+        writeln!(file, "{}", serde_json::to_string(record)?)?;
+    }
+    Ok(())
+}
+
+/// All records of the history at `path`, oldest first. A missing file is an empty history.
+pub fn load(path: &Path) -> Result<Vec<Record>, AocError> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e.into()),
+    };
+    let mut records = vec![];
+    for (no, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            records.push(serde_json::from_str(&line).map_err(|e| AocError::BadHistory(no + 1, e))?);
+        }
+    }
+    Ok(records)
+}
+
+/// A trend line per day and bench: median times and peak heap bytes over the records, oldest
+/// to latest, like:
+///
+/// ```text
+///     day-01 part1        time ▁▁▃█▂      74.33 µs
+///     day-01 part1        heap ▁▁▁▁▁       1032 B
+/// ```
+pub fn chart<'r>(records: impl IntoIterator<Item = &'r Record>) -> String {
+    let mut series = BTreeMap::<(u8, &str), (Vec<f64>, Vec<f64>)>::new();
+    for record in records {
+        let (times, heaps) = series.entry((record.day, &record.bench)).or_default();
+        if let Some(timing) = record.timing {
+            times.push(timing.median_ns);
+        }
+        if let Some(allocations) = record.allocations {
+            heaps.push(allocations.peak_bytes as f64);
+        }
+    }
+
+    let mut chart = String::new();
+    for ((day, bench), (times, heaps)) in series {
+        if let Some(latest) = times.last() {
+            let _ = writeln!(
+                chart,
+                "day-{day:02} {bench:<12} time {:<24} {:>10}",
+                sparkline(&times),
+                format_ns(*latest)
+            );
+        }
+        if let Some(latest) = heaps.last() {
+            let _ = writeln!(
+                chart,
+                "day-{day:02} {bench:<12} heap {:<24} {:>10}",
+                sparkline(&heaps),
+                format!("{latest} B")
+            );
+        }
+    }
+    chart
+}
+
+/// Values scaled to eight bar heights, the last 24 at most
+fn sparkline(values: &[f64]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let values = &values[values.len().saturating_sub(24)..];
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    values
+        .iter()
+        .map(|v| match max - min {
+            range if range > 0.0 => BARS[((v - min) / range * 7.0).round() as usize],
+            _ => BARS[0],
+        })
+        .collect()
+}
+
+/// Nanoseconds the way divan prints them
+pub fn format_ns(ns: f64) -> String {
+    match ns {
+        ns if ns >= 1e9 => format!("{:.3} s", ns / 1e9),
+        ns if ns >= 1e6 => format!("{:.2} ms", ns / 1e6),
+        ns if ns >= 1e3 => format!("{:.2} µs", ns / 1e3),
+        ns => format!("{ns:.0} ns"),
+    }
+}
+
+/// Parse a divan table header like `day_01    fastest       │ slowest ...`
+fn table_header(line: &str) -> IResult<&str, u8> {
+    terminated(
+        preceded(tag("day_"), map_res(digit1, str::parse)),
+        pair(space1, tag("fastest")),
+    )(line)
+}
+
+/// Split a divan table row like `├─ part1  73.57 µs │ 109.3 µs │ ...` into the depth of the
+/// bench in the tree, its name and the remaining columns.
+fn table_row(line: &str) -> Option<(usize, &str, &str)> {
+    let branch = line.find("├─ ").or_else(|| line.find("╰─ "))?;
+    let prefix = &line[..branch];
+    if !prefix.chars().all(|c| c == '│' || c == ' ') {
+        return None;
+    }
+    let depth = prefix.chars().count() / 3;
+    let rest = &line[branch + "├─ ".len()..];
+    let (name, columns) = rest.split_once(' ').unwrap_or((rest, ""));
+    Some((depth, name, columns))
+}
+
+/// Fastest, slowest, median and mean columns, `None` if they don't all hold a duration
+fn timing(columns: &str) -> Option<Timing> {
+    let durations = columns
+        .split('│')
+        .take(4)
+        .map(|column| {
+            all_consuming(duration)(column.trim())
+                .ok()
+                .map(|(_, ns)| ns)
+        })
+        .collect::<Option<Vec<_>>>()?;
+    match durations[..] {
+        [fastest_ns, _, median_ns, mean_ns] => Some(Timing {
+            fastest_ns,
+            median_ns,
+            mean_ns,
+        }),
+        _ => None,
+    }
+}
+
+/// Parse a duration like `73.57 µs` into nanoseconds.
+fn duration(i: &str) -> IResult<&str, f64> {
+    pair(
+        terminated(double, space0),
+        alt((
+            tag("ns").map(|_| 1.0),
+            tag("µs").map(|_| 1e3),
+            tag("us").map(|_| 1e3),
+            tag("ms").map(|_| 1e6),
+            tag("s").map(|_| 1e9),
+        )),
+    )
+    .map(|(value, unit)| value * unit)
+    .parse(i)
+}
+
+/// Day and bin of a command like `cargo run ... --package day-01 --bin part1`
+fn dhat_run(line: &str) -> Option<(u8, String)> {
+    let mut words = line.split_whitespace();
+    let (mut day, mut bin) = (None, None);
+    while let Some(word) = words.next() {
+        match word {
+            "--package" | "-p" => day = words.next()?.strip_prefix("day-")?.parse().ok(),
+            "--bin" => bin = words.next().map(str::to_string),
+            _ => {}
+        }
+    }
+    day.zip(bin)
+}
+
+/// Parse a line like `dhat: At t-gmax: 881,216 bytes in 1,785 blocks` into whether it's the
+/// peak (or the total), bytes and blocks.
+fn dhat_summary(line: &str) -> IResult<&str, (bool, u64, u64)> {
+    let (i, peak) = preceded(
+        tag("dhat: "),
+        alt((
+            tag("Total:").map(|_| false),
+            tag("At t-gmax:").map(|_| true),
+        )),
+    )(line)?;
+    let (i, bytes) = preceded(space1, grouped_number)(i)?;
+    let (i, blocks) = preceded(tag(" bytes in "), grouped_number)(i)?;
+    let (i, _) = tag(" blocks")(i)?;
+    Ok((i, (peak, bytes, blocks)))
+}
+
+/// A number with thousands separated by commas, like `1,846,072`
+fn grouped_number(i: &str) -> IResult<&str, u64> {
+    map_res(
+        recognize(separated_list1(tag(","), digit1)),
+        |digits: &str| digits.replace(',', "").parse(),
+    )(i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STAMP: Stamp = Stamp {
+        timestamp: 1_702_339_200,
+        commit: None,
+    };
+
+    #[test]
+    fn test_parse_report() {
+        let text = "Day 4 looking fine
+Timer precision: 17 ns
+day_03    fastest       │ slowest       │ median        │ mean          │ samples │ iters
+├─ part1  1.46 ms       │ 1.854 ms      │ 1.475 ms      │ 1.486 ms      │ 100     │ 100
+╰─ part2  thread 'main' panicked at 'not yet implemented: day part 2', day-03/src/part2.rs:7:5
+
+cargo run -q --profile dhat --features dhat-heap --package day-03 --bin part1
+531611
+dhat: Total:     1,846,072 bytes in 5,386 blocks
+dhat: At t-gmax: 881,216 bytes in 1,785 blocks
+dhat: At t-end:  1,024 bytes in 1 blocks
+
+day_09           fastest       │ slowest       │ median        │ mean          │ samples │ iters
+╰─ extrapolate                 │               │               │               │         │
+   ├─ engine     79.43 µs      │ 192.6 µs      │ 132.7 µs      │ 124.7 µs      │ 100     │ 100";
+        let records = parse_report(text, &STAMP);
+        assert_eq!(3, records.len());
+
+        assert_eq!((3, "part1"), (records[0].day, records[0].bench.as_str()));
+        assert_eq!(
+            Some(Timing {
+                fastest_ns: 1.46e6,
+                median_ns: 1.475e6,
+                mean_ns: 1.486e6
+            }),
+            records[0].timing
+        );
+        assert_eq!(
+            Some(Allocations {
+                total_bytes: 1_846_072,
+                total_blocks: 5_386,
+                peak_bytes: 881_216,
+                peak_blocks: 1_785
+            }),
+            records[1].allocations
+        );
+        assert_eq!("extrapolate/engine", records[2].bench);
+        assert_eq!(132.7e3, records[2].timing.unwrap().median_ns);
+    }
+
+    #[test]
+    fn test_parse_report_peak_without_total() {
+        let text = "cargo run -q --profile dhat --features dhat-heap --package day-03 --bin part1
+dhat: Total:     1,846,072 bytes in 5,386 blocks
+dhat: At t-gmax: 881,216 bytes in 1,785 blocks
+
+cargo run -q --profile dhat --features dhat-heap
+dhat: Total:     2,000,000 bytes in 6,000 blocks
+dhat: At t-gmax: 999,999 bytes in 2,000 blocks
+
+cargo run -q --profile dhat --features dhat-heap --package day-03 --bin part2
+dhat: At t-gmax: 777,777 bytes in 1,000 blocks";
+        let records = parse_report(text, &STAMP);
+        assert_eq!(1, records.len());
+        assert_eq!(881_216, records[0].allocations.unwrap().peak_bytes);
+    }
+
+    #[test]
+    fn test_chart() {
+        let text = "day_01    fastest       │ slowest       │ median        │ mean          │ samples │ iters
+╰─ part1  73.57 µs      │ 109.3 µs      │ 74.33 µs      │ 76.67 µs      │ 100     │ 100";
+        let mut records = parse_report(text, &STAMP);
+        records.push(Record {
+            timing: records[0].timing.map(|timing| Timing {
+                median_ns: 2.0 * timing.median_ns,
+                ..timing
+            }),
+            ..records[0].clone()
+        });
+        assert_eq!(
+            "day-01 part1        time ▁█                        148.66 µs\n",
+            chart(&records)
+        );
+    }
+}
@@ -0,0 +1,10 @@
+pub mod custom_error;
+
+pub mod alloc;
+pub mod days;
+pub mod flame;
+pub mod gate;
+pub mod history;
+pub mod run;
+pub mod stress;
+pub mod trace;
@@ -0,0 +1,402 @@
+//! Run, measure and profile the days' solutions, e.g.
+//!
+//!     cargo run -p runner -- history import benchmarks.txt
+//!     cargo bench -q -p day-09 | cargo run -p runner -- history import -
+//!     cargo run -p runner -- history chart --day 9
+//!     cargo run -p runner -- gate --day 3 --day 9
+//!     cargo run -p runner -- alloc --day 5
+//!     cargo run --profile flamegraph -p runner -- flamegraph --seconds 1
+//!     cargo run --release -p runner -- stress --day 3 --scale 50
+//!     cargo run --release -p runner --features parallel -- run --all --threads 4
+//!     cargo run -p runner -- export --mermaid --collapse --out network.mmd
+//!     cargo run --release -p runner -- run --day 5 --chrome-trace trace.json
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use clap::{Parser, Subcommand};
+use miette::{miette, IntoDiagnostic};
+use runner::{
+    alloc::{self, Counting},
+    custom_error::AocError,
+    days,
+    flame::{self, FLAMEGRAPHS},
+    gate::{self, Baseline, Verdict, BASELINE},
+    history::{self, Record, Stamp, HISTORY},
+    run,
+    stress::{self, EXPECTED},
+    trace,
+};
+
+#[global_allocator]
+static ALLOC: Counting = Counting;
+
+#[derive(Parser)]
+#[command(about = "Run, measure and profile the Advent of Code solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Write the spans and events as a Chrome trace to this file
+    #[arg(long, global = true)]
+    chrome_trace: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Benchmark history kept as JSON lines
+    History {
+        #[command(subcommand)]
+        command: HistoryCommand,
+        /// History file to read and append to
+        #[arg(long, default_value = HISTORY, global = true)]
+        history: PathBuf,
+    },
+    /// Run the day benches and fail if any got slower or heavier than the baseline allows
+    Gate {
+        /// Day to run, all benched days if not given
+        #[arg(long = "day")]
+        days: Vec<u8>,
+        /// Baseline file to compare against, bench-baseline.json in the workspace if not given
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+        /// Skip counting allocations, compare times only
+        #[arg(long)]
+        no_heap: bool,
+        /// Store the measurements as the new baseline instead of comparing
+        #[arg(long)]
+        update: bool,
+    },
+    /// Count bytes and blocks allocated parsing and solving each part
+    Alloc {
+        /// Day to run, all days if not given
+        #[arg(long = "day")]
+        days: Vec<u8>,
+        /// Directory for the per-day JSON files
+        #[arg(long, default_value = "alloc-stats")]
+        out: PathBuf,
+    },
+    /// Sample each part run in a loop and write flamegraphs with an index page
+    Flamegraph {
+        /// Day to run, all days if not given
+        #[arg(long = "day")]
+        days: Vec<u8>,
+        /// How long to loop each part
+        #[arg(long, default_value_t = 2.0)]
+        seconds: f64,
+        /// Samples per second
+        #[arg(long, default_value_t = 999)]
+        frequency: i32,
+        /// Directory for the folded stacks, SVGs and index.html
+        #[arg(long, default_value = FLAMEGRAPHS)]
+        out: PathBuf,
+        /// Benchmark history to take the latest numbers from
+        #[arg(long, default_value = HISTORY)]
+        history: PathBuf,
+    },
+    /// Solve parts side by side on a thread pool and print their answers and times
+    Run {
+        /// Day to run
+        #[arg(long = "day", required_unless_present = "all")]
+        days: Vec<u8>,
+        /// Run every day
+        #[arg(long, conflicts_with = "days")]
+        all: bool,
+        /// Threads in the pool, one per core if 0
+        #[arg(long, default_value_t = 0)]
+        threads: usize,
+    },
+    /// Time each part on generated inputs growing past the real one and fit how it scales
+    Stress {
+        /// Day to run, all days if not given
+        #[arg(long = "day")]
+        days: Vec<u8>,
+        /// Largest input to try, as a multiple of the real one
+        #[arg(long, default_value_t = 100.0)]
+        scale: f64,
+        /// Seed for the input generators
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// How long to loop each part on each input
+        #[arg(long, default_value_t = 0.1)]
+        seconds: f64,
+        /// How far above the expected exponent a part may scale
+        #[arg(long, default_value_t = 0.25)]
+        tolerance: f64,
+    },
+    /// Draw day 8's desert network as a Graphviz DOT graph, or a Mermaid flowchart
+    Export {
+        /// Mermaid instead of DOT
+        #[arg(long)]
+        mermaid: bool,
+        /// Draw nodes no walk can tell apart as one, labelled with how many it hides
+        #[arg(long)]
+        collapse: bool,
+        /// File to write, stdout if not given
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum HistoryCommand {
+    /// Append divan tables and dhat summaries found in a file ('-' for stdin)
+    Import {
+        #[arg(default_value = "benchmarks.txt")]
+        file: PathBuf,
+        /// Seconds since the Unix epoch to record instead of now
+        #[arg(long)]
+        timestamp: Option<u64>,
+        /// Commit to record. Defaults to the checked out one for stdin, which holds fresh
+        /// output, and to none for a file, which may be older.
+        #[arg(long)]
+        commit: Option<String>,
+    },
+    /// Print trend charts of median times and peak heap bytes
+    Chart {
+        /// Only this day
+        #[arg(long)]
+        day: Option<u8>,
+        /// Only this bench, like 'part1'
+        #[arg(long)]
+        bench: Option<String>,
+    },
+}
+
+fn main() -> miette::Result<()> {
+    let cli = Cli::parse();
+    let _trace = trace::init(cli.chrome_trace.as_deref());
+
+    match cli.command {
+        Command::History { command, history } => match command {
+            HistoryCommand::Import {
+                file,
+                timestamp,
+                commit,
+            } => import(&file, timestamp, commit, &history),
+            HistoryCommand::Chart { day, bench } => {
+                let records = history::load(&history)?;
+                let selected = records.iter().filter(|record: &&Record| {
+                    day.is_none_or(|day| record.day == day)
+                        && bench.as_ref().is_none_or(|bench| &record.bench == bench)
+                });
+                print!("{}", history::chart(selected));
+                Ok(())
+            }
+        },
+        Command::Gate {
+            days,
+            baseline,
+            no_heap,
+            update,
+        } => {
+            let baseline = baseline.unwrap_or_else(|| gate::workspace_root().join(BASELINE));
+            run_gate(days, &baseline, !no_heap, update)
+        }
+        Command::Alloc { days, out } => run_alloc(&days, &out),
+        Command::Flamegraph {
+            days,
+            seconds,
+            frequency,
+            out,
+            history,
+        } => run_flamegraph(&days, seconds, frequency, &out, &history),
+        Command::Run { days, threads, .. } => {
+            let run = run::run(&selected_parts(&days)?, threads)?;
+            print!("{}", run::table(&run));
+            Ok(())
+        }
+        Command::Stress {
+            days,
+            scale,
+            seed,
+            seconds,
+            tolerance,
+        } => run_stress(&days, scale, seed, seconds, tolerance),
+        Command::Export {
+            mermaid,
+            collapse,
+            out,
+        } => run_export(mermaid, collapse, out.as_deref()),
+    }
+}
+
+fn run_export(mermaid: bool, collapse: bool, out: Option<&Path>) -> miette::Result<()> {
+    let network = day_08::network::Network::parse(include_str!("../../day-08/input1.txt"))?;
+    let graph = match mermaid {
+        true => day_08::export::to_mermaid(&network, collapse),
+        false => day_08::export::to_dot(&network, collapse),
+    };
+    match out {
+        Some(out) => std::fs::write(out, graph).map_err(AocError::from)?,
+        None => print!("{graph}"),
+    }
+    Ok(())
+}
+
+/// The parts of `days`, all of them if none are given
+fn selected_parts(days: &[u8]) -> miette::Result<Vec<days::Part>> {
+    let parts = days::parts();
+    if let Some(&day) = days
+        .iter()
+        .find(|&&day| parts.iter().all(|part| part.day != day))
+    {
+        return Err(AocError::UnknownDay(day).into());
+    }
+    Ok(parts
+        .into_iter()
+        .filter(|part| days.is_empty() || days.contains(&part.day))
+        .collect())
+}
+
+fn run_flamegraph(
+    days: &[u8],
+    seconds: f64,
+    frequency: i32,
+    out: &Path,
+    history: &Path,
+) -> miette::Result<()> {
+    let duration = Duration::try_from_secs_f64(seconds).into_diagnostic()?;
+    let mut profiles = vec![];
+    for part in selected_parts(days)? {
+        eprintln!("Profiling {}...", part.name());
+        profiles.push(flame::profile(&part, duration, frequency)?);
+    }
+
+    // The baseline fills in for parts the history hasn't seen.
+    let mut records = Baseline::load_or_default(&gate::workspace_root().join(BASELINE))?.records;
+    records.extend(history::load(history)?);
+    flame::save(out, &profiles, &records)?;
+    println!("Open {}", out.join("index.html").display());
+    Ok(())
+}
+
+fn run_stress(
+    days: &[u8],
+    scale: f64,
+    seed: u64,
+    seconds: f64,
+    tolerance: f64,
+) -> miette::Result<()> {
+    let min_time = Duration::try_from_secs_f64(seconds).into_diagnostic()?;
+    let mut scalings = vec![];
+    let mut skipped = vec![];
+    for part in selected_parts(days)? {
+        eprintln!("Stressing {}...", part.name());
+        match stress::stress(&part, seed, scale, min_time)? {
+            Some(scaling) => scalings.push(scaling),
+            None => skipped.push(part.name()),
+        }
+    }
+
+    print!("{}", stress::table(&scalings, tolerance));
+    if !skipped.is_empty() {
+        println!(
+            "Skipped, fewer than two generated sizes within {scale}x: {}",
+            skipped.join(", ")
+        );
+    }
+    let worse = scalings
+        .iter()
+        .filter(|s| s.worse(tolerance))
+        .map(|s| format!("day-{:02} part{}", s.day, s.part))
+        .collect::<Vec<_>>();
+    if !worse.is_empty() {
+        println!(
+            "Scaling worse than bytes^{:.2}: {}",
+            EXPECTED + tolerance,
+            worse.join(", ")
+        );
+    }
+    Ok(())
+}
+
+fn run_alloc(days: &[u8], out: &Path) -> miette::Result<()> {
+    let reports = selected_parts(days)?
+        .iter()
+        .map(alloc::report)
+        .collect::<miette::Result<Vec<_>>>()?;
+    print!("{}", alloc::table(&reports));
+    alloc::save(out, &reports)?;
+    println!("Saved to {}", out.display());
+    Ok(())
+}
+
+fn run_gate(mut days: Vec<u8>, path: &Path, heap: bool, update: bool) -> miette::Result<()> {
+    // Reading the baseline first fails a mistyped path before minutes of benching.
+    let mut baseline = match update {
+        true => Baseline::load_or_default(path)?,
+        false => Baseline::load(path)?,
+    };
+    if days.is_empty() {
+        let root = gate::workspace_root();
+        days = gate::benched_days(root)?;
+        if days.is_empty() {
+            return Err(AocError::NoBenches(root.display().to_string()).into());
+        }
+    }
+    let stamp = Stamp::now();
+    let mut records = vec![];
+    for day in days {
+        eprintln!("Measuring day-{day:02}...");
+        records.extend(gate::measure(day, heap, &stamp)?);
+    }
+
+    if update {
+        baseline.update(&records);
+        baseline.save(path)?;
+        println!(
+            "{} benches stored in {}",
+            baseline.records.len(),
+            path.display()
+        );
+        return Ok(());
+    }
+
+    let comparisons = gate::compare(&baseline, &records);
+    print!("{}", gate::table(&comparisons));
+    match comparisons
+        .iter()
+        .filter(|c| c.verdict == Verdict::Regressed)
+        .count()
+    {
+        0 => Ok(()),
+        n => Err(miette!("{n} regressions against {}", path.display())),
+    }
+}
+
+fn import(
+    file: &Path,
+    timestamp: Option<u64>,
+    commit: Option<String>,
+    history: &Path,
+) -> miette::Result<()> {
+    let stdin = file.to_str() == Some("-");
+    let text = match stdin {
+        true => {
+            let mut text = String::new();
+            std::io::stdin()
+                .read_to_string(&mut text)
+                .into_diagnostic()?;
+            text
+        }
+        false => std::fs::read_to_string(file).into_diagnostic()?,
+    };
+
+    let mut stamp = Stamp::now();
+    if let Some(timestamp) = timestamp {
+        stamp.timestamp = timestamp;
+    }
+    if commit.is_some() || !stdin {
+        stamp.commit = commit;
+    }
+    let records = history::parse_report(&text, &stamp);
+    history::append(history, &records)?;
+    println!(
+        "{} records appended to {}",
+        records.len(),
+        history.display()
+    );
+    Ok(())
+}
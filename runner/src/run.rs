@@ -0,0 +1,136 @@
+use std::{
+    fmt::Write as _,
+    time::{Duration, Instant},
+};
+
+use rayon::prelude::*;
+use tracing::info_span;
+
+use crate::{custom_error::AocError, days::Part, history::format_ns};
+
+/// A part's answer and how long it took on its worker
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outcome {
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub elapsed: Duration,
+}
+
+/// Outcomes of parts run side by side
+#[derive(Debug, Clone, PartialEq)]
+pub struct Run {
+    /// In the order of the parts given
+    pub outcomes: Vec<Outcome>,
+    pub threads: usize,
+    pub wall: Duration,
+    /// User and system time of the whole process over the run
+    pub cpu: Duration,
+}
+
+impl Run {
+    /// What the parts would take one after the other
+    pub fn summed(&self) -> Duration {
+        self.outcomes.iter().map(|outcome| outcome.elapsed).sum()
+    }
+}
+
+/// Run `parts` on a pool of `threads` threads, one per core if 0. Parts solving on the pool
+/// themselves (the `parallel` feature) share it.
+pub fn run(parts: &[Part], threads: usize) -> miette::Result<Run> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .map_err(AocError::ThreadPoolError)?;
+
+    let cpu = cpu_time();
+    let start = Instant::now();
+    let outcomes = pool.install(|| {
+        parts
+            .par_iter()
+            .map(|part| {
+                let _span = info_span!("day", day = part.day, part = part.part).entered();
+                let start = Instant::now();
+                let answer = (part.process)(part.input)?;
+                Ok(Outcome {
+                    day: part.day,
+                    part: part.part,
+                    answer,
+                    elapsed: start.elapsed(),
+                })
+            })
+            .collect::<miette::Result<Vec<_>>>()
+    })?;
+    Ok(Run {
+        outcomes,
+        threads: pool.current_num_threads(),
+        wall: start.elapsed(),
+        cpu: cpu_time().saturating_sub(cpu),
+    })
+}
+
+/// User and system time the process has used so far
+fn cpu_time() -> Duration {
+    let mut usage = std::mem::MaybeUninit::<libc::rusage>::uninit();
+    // SAFETY: getrusage only writes the struct it is given.
+    let usage = unsafe {
+        if libc::getrusage(libc::RUSAGE_SELF, usage.as_mut_ptr()) != 0 {
+            return Duration::ZERO;
+        }
+        usage.assume_init()
+    };
+    let time = |t: libc::timeval| {
+        Duration::from_secs(t.tv_sec as u64) + Duration::from_micros(t.tv_usec as u64)
+    };
+    time(usage.ru_utime) + time(usage.ru_stime)
+}
+
+/// Answers and times per part, then wall time against the summed and CPU times
+pub fn table(run: &Run) -> String {
+    let mut table = format!("{:<13} {:>12} {:>20}\n", "part", "time", "answer");
+    for o in &run.outcomes {
+        let _ = writeln!(
+            table,
+            "day-{:02} part{} {:>12} {:>20}",
+            o.day,
+            o.part,
+            format_ns(o.elapsed.as_nanos() as f64),
+            o.answer
+        );
+    }
+    let wall = run.wall.as_nanos() as f64;
+    let _ = writeln!(
+        table,
+        "{} threads: wall {}, parts summed {} ({:.1}x), CPU {} ({:.1}x)",
+        run.threads,
+        format_ns(wall),
+        format_ns(run.summed().as_nanos() as f64),
+        run.summed().as_nanos() as f64 / wall,
+        format_ns(run.cpu.as_nanos() as f64),
+        run.cpu.as_nanos() as f64 / wall,
+    );
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days;
+
+    #[test]
+    fn test_run() -> miette::Result<()> {
+        let parts = days::parts()
+            .into_iter()
+            .filter(|part| [2, 9].contains(&part.day))
+            .collect::<Vec<_>>();
+        let run = run(&parts, 2)?;
+        assert_eq!(2, run.threads);
+        assert_eq!(4, run.outcomes.len());
+        for (part, outcome) in parts.iter().zip(&run.outcomes) {
+            assert_eq!((part.day, part.part), (outcome.day, outcome.part));
+            assert_eq!((part.process)(part.input)?, outcome.answer);
+        }
+        assert!(table(&run).contains("2 threads"));
+        Ok(())
+    }
+}
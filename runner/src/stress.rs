@@ -0,0 +1,188 @@
+use std::{
+    fmt::Write as _,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use crate::{custom_error::AocError, days::Part, history::format_ns};
+
+/// Exponent of the input size every part should stay near: each puzzle can be solved in
+/// linear time, give or take a log for sorting.
+pub const EXPECTED: f64 = 1.0;
+
+/// A part's time on one generated input
+#[derive(Debug, Clone, PartialEq)]
+pub struct Point {
+    /// What the generator was asked for
+    pub size: usize,
+    pub bytes: usize,
+    /// Mean time of a run
+    pub ns: f64,
+}
+
+/// How a part's time grows with its input
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scaling {
+    pub day: u8,
+    pub part: u8,
+    /// Length of the real puzzle input
+    pub real_bytes: usize,
+    pub points: Vec<Point>,
+    /// `k` in time ~ bytes^k, fitted over the points
+    pub exponent: f64,
+}
+
+impl Scaling {
+    /// Whether time grows faster than bytes^(`EXPECTED` + `tolerance`)
+    pub fn worse(&self, tolerance: f64) -> bool {
+        self.exponent > EXPECTED + tolerance
+    }
+}
+
+/// Time `part` on generated inputs from the length of the real one up to `scale` times that,
+/// doubling the generator's size each step up to the part's `max_size`, and stopping early if
+/// inputs stop growing. Each size runs for `min_time` (at least once), and answers the generator
+/// knows are checked. `None` if fewer than two sizes fit, too few to fit a scaling to.
+pub fn stress(
+    part: &Part,
+    seed: u64,
+    scale: f64,
+    min_time: Duration,
+) -> miette::Result<Option<Scaling>> {
+    let real_bytes = part.input.len();
+    let mut points: Vec<Point> = vec![];
+    let sizes = std::iter::successors(Some(1usize), |&size| {
+        (size < part.max_size).then(|| size.saturating_mul(2).min(part.max_size))
+    });
+    for size in sizes {
+        let (input, answer) = (part.generate)(seed, size);
+        let bytes = input.len();
+        let capped = points.last().is_some_and(|last| bytes <= last.bytes);
+        if bytes as f64 > scale * real_bytes as f64 || capped {
+            break;
+        }
+        if bytes < real_bytes {
+            continue;
+        }
+
+        let output = (part.process)(&input)?;
+        if let Some(answer) = answer.filter(|answer| *answer != output) {
+            return Err(AocError::WrongAnswer(part.name(), output, size, answer).into());
+        }
+        let start = Instant::now();
+        let mut runs = 0;
+        while runs == 0 || start.elapsed() < min_time {
+            black_box((part.process)(black_box(&input))?);
+            runs += 1;
+        }
+        points.push(Point {
+            size,
+            bytes,
+            ns: start.elapsed().as_nanos() as f64 / runs as f64,
+        });
+    }
+
+    if points.len() < 2 {
+        return Ok(None);
+    }
+    Ok(Some(Scaling {
+        day: part.day,
+        part: part.part,
+        real_bytes,
+        exponent: fit(&points),
+        points,
+    }))
+}
+
+/// Slope of the least squares line through the points on log-log axes
+pub fn fit(points: &[Point]) -> f64 {
+    let logs = points
+        .iter()
+        .map(|p| ((p.bytes as f64).ln(), p.ns.ln()))
+        .collect::<Vec<_>>();
+    let n = logs.len() as f64;
+    let mean_x = logs.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = logs.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let covariance = logs
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum::<f64>();
+    let variance = logs.iter().map(|(x, _)| (x - mean_x).powi(2)).sum::<f64>();
+    covariance / variance
+}
+
+/// Sizes tried, input range against the real input, time range and fitted exponent per part
+pub fn table(scalings: &[Scaling], tolerance: f64) -> String {
+    let mut table = format!(
+        "{:<13} {:>5} {:>15} {:>23} {:>8}  verdict\n",
+        "part", "sizes", "input", "time", "exponent"
+    );
+    for s in scalings {
+        let (first, last) = (&s.points[0], &s.points[s.points.len() - 1]);
+        let times = |p: &Point| p.bytes as f64 / s.real_bytes as f64;
+        let _ = writeln!(
+            table,
+            "day-{:02} part{} {:>5} {:>15} {:>23} {:>8.2}  {}",
+            s.day,
+            s.part,
+            s.points.len(),
+            format!("{:.1}x..{:.1}x", times(first), times(last)),
+            format!("{}..{}", format_ns(first.ns), format_ns(last.ns)),
+            s.exponent,
+            if s.worse(tolerance) { "worse" } else { "ok" }
+        );
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days;
+
+    fn point(bytes: usize, ns: f64) -> Point {
+        Point { size: 0, bytes, ns }
+    }
+
+    #[test]
+    fn test_fit() {
+        let linear = [point(10, 5.0), point(20, 10.0), point(40, 20.0)];
+        assert!((fit(&linear) - 1.0).abs() < 1e-9);
+        let quadratic = [point(10, 100.0), point(100, 10_000.0)];
+        assert!((fit(&quadratic) - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_stress() -> miette::Result<()> {
+        let parts = days::parts();
+        let part = parts.iter().find(|part| part.day == 2).unwrap();
+        let scaling = stress(part, 0, 8.0, Duration::ZERO)?.unwrap();
+        assert!(scaling.points.len() >= 3);
+        assert!(scaling.points.iter().all(|p| p.bytes >= part.input.len()));
+        assert!(scaling
+            .points
+            .iter()
+            .all(|p| p.bytes <= 8 * part.input.len()));
+        assert!(table(&[scaling], 0.25).contains("day-02 part1"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_stress_skips() -> miette::Result<()> {
+        let parts = days::parts();
+        // No room for two sizes between the real input and itself.
+        let part = parts.iter().find(|part| part.day == 3).unwrap();
+        assert_eq!(None, stress(part, 0, 1.0, Duration::ZERO)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_stress_max_size() -> miette::Result<()> {
+        let parts = days::parts();
+        let part = parts.iter().find(|part| part.day == 8).unwrap();
+        let scaling = stress(part, 0, 1000.0, Duration::ZERO)?.unwrap();
+        let sizes = scaling.points.iter().map(|p| p.size).collect::<Vec<_>>();
+        assert_eq!(Some(&part.max_size), sizes.last());
+        Ok(())
+    }
+}
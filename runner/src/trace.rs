@@ -0,0 +1,60 @@
+use std::path::Path;
+
+use tracing::{level_filters::LevelFilter, Subscriber};
+use tracing_chrome::{ChromeLayer, ChromeLayerBuilder, FlushGuard};
+use tracing_subscriber::{layer::SubscriberExt, registry::LookupSpan, Layer};
+
+/// Layer writing spans and events to `path` in the Chrome trace format, for chrome://tracing
+/// or Perfetto. The file is complete once the guard is dropped.
+pub fn chrome<S>(path: &Path) -> (ChromeLayer<S>, FlushGuard)
+where
+    S: Subscriber + for<'span> LookupSpan<'span> + Send + Sync,
+{
+    ChromeLayerBuilder::new()
+        .file(path)
+        .include_args(true)
+        .build()
+}
+
+/// Log to stderr like the day binaries do, and trace everything to `chrome_trace` if given.
+/// Hold on to the guard until the end of `main`.
+pub fn init(chrome_trace: Option<&Path>) -> Option<FlushGuard> {
+    let (layer, guard) = chrome_trace.map(chrome).unzip();
+    let subscriber = tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_filter(LevelFilter::INFO))
+        .with(layer);
+    tracing::subscriber::set_global_default(subscriber).expect("tracing is set up once");
+    guard
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{custom_error::AocError, days};
+
+    #[test]
+    fn test_chrome() -> miette::Result<()> {
+        let path = std::env::temp_dir().join(format!("runner-trace-{}.json", std::process::id()));
+        let (layer, guard) = chrome(&path);
+        let subscriber = tracing_subscriber::registry().with(layer);
+        let part = days::parts()
+            .into_iter()
+            .find(|part| part.day == 3)
+            .unwrap();
+        tracing::subscriber::with_default(subscriber, || (part.process)(part.input))?;
+        drop(guard);
+
+        let text = std::fs::read_to_string(&path).map_err(AocError::from)?;
+        std::fs::remove_file(&path).map_err(AocError::from)?;
+        let trace: serde_json::Value = serde_json::from_str(&text).map_err(AocError::from)?;
+        let names = trace
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|event| event["ph"] == "B")
+            .map(|event| event["name"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["process", "parse", "solve"], names);
+        Ok(())
+    }
+}